@@ -0,0 +1,149 @@
+// generates intcode.h, the C header for the functions in src/ffi.rs, in
+// OUT_DIR; cargo reports that directory in its build-script-executed message,
+// which is how c/Makefile finds the header
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const SOURCE: &str = "src/ffi.rs";
+
+fn c_type(rust: &str) -> Result<String, String> {
+    let rust = rust.trim();
+
+    let (qualifier, pointee) = match rust.split_once(' ') {
+        Some(("*const", pointee)) => ("const ", pointee),
+        Some(("*mut", pointee)) => ("", pointee),
+        _ => ("", rust),
+    };
+
+    let base = match pointee {
+        "i32" => "int32_t",
+        "usize" => "size_t",
+        "Computer" => "intcode_computer",
+        _ => return Err(format!("no C type for {}", rust)),
+    };
+
+    match pointee == rust {
+        true => Ok(base.to_string()),
+        false => Ok(format!("{}{} *", qualifier, base)),
+    }
+}
+
+fn declaration(signature: &str) -> Result<String, String> {
+    let signature = signature.trim_start_matches("pub unsafe extern \"C\" fn ");
+
+    let (name, rest) = signature.split_once('(')
+        .ok_or_else(|| format!("malformed signature: {}", signature))?;
+
+    let (params, rest) = rest.rsplit_once(')')
+        .ok_or_else(|| format!("malformed signature: {}", signature))?;
+
+    let params = params.split(',')
+        .map(str::trim)
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (name, rust) = param.split_once(':')
+                .ok_or_else(|| format!("malformed parameter: {}", param))?;
+            let c = c_type(rust)?;
+
+            match c.ends_with('*') {
+                true => Ok(format!("{}{}", c, name.trim())),
+                false => Ok(format!("{} {}", c, name.trim())),
+            }
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+
+    let result = match rest.trim().trim_end_matches('{').trim().strip_prefix("->") {
+        Some(rust) => c_type(rust)?,
+        None => "void".to_string(),
+    };
+
+    let params = match params.is_empty() {
+        true => "void".to_string(),
+        false => params.join(", "),
+    };
+
+    match result.ends_with('*') {
+        true => Ok(format!("{}{}({});", result, name.trim(), params)),
+        false => Ok(format!("{} {}({});", result, name.trim(), params)),
+    }
+}
+
+fn generate(source: &str) -> Result<String, String> {
+    let mut header = String::from(
+        "/* generated by build.rs from src/ffi.rs; do not edit */\n\n\
+         #ifndef INTCODE_H\n\
+         #define INTCODE_H\n\n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\n\
+         #ifdef __cplusplus\n\
+         extern \"C\" {\n\
+         #endif\n\n\
+         typedef struct intcode_computer intcode_computer;\n\n",
+    );
+
+    let lines = source.lines().collect::<Vec<&str>>();
+    let mut comments = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index].trim();
+        index += 1;
+
+        if let Some(comment) = line.strip_prefix("// ") {
+            comments.push(comment);
+            continue;
+        }
+
+        if let Some(constant) = line.strip_prefix("pub const ") {
+            let (name, value) = constant.split_once(':')
+                .and_then(|(name, rest)| Some((name, rest.split_once('=')?.1)))
+                .ok_or_else(|| format!("malformed constant: {}", line))?;
+
+            let value = value.trim().trim_end_matches(';');
+
+            header += &match value.starts_with('-') {
+                true => format!("#define {} ({})\n", name.trim(), value),
+                false => format!("#define {} {}\n", name.trim(), value),
+            };
+        }
+
+        if line.starts_with("pub unsafe extern \"C\" fn") {
+            let mut signature = line.to_string();
+
+            while !signature.ends_with('{') {
+                signature += " ";
+                signature += lines[index].trim();
+                index += 1;
+            }
+
+            header += "\n";
+
+            for comment in comments.iter() {
+                header += &format!("/* {} */\n", comment);
+            }
+
+            header += &declaration(&signature)?;
+            header += "\n";
+        }
+
+        if line.is_empty() || !line.starts_with("#[") {
+            comments.clear();
+        }
+    }
+
+    header += "\n#ifdef __cplusplus\n}\n#endif\n\n#endif\n";
+
+    Ok(header)
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SOURCE);
+
+    let source = fs::read_to_string(SOURCE).unwrap();
+    let header = generate(&source).unwrap_or_else(|e| panic!("{}: {}", SOURCE, e));
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join("intcode.h"), header).unwrap();
+}
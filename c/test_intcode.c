@@ -0,0 +1,82 @@
+/* exercises the C interface against the library built by cargo */
+
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "intcode.h"
+
+static int failures = 0;
+
+#define CHECK(condition) do { \
+    if (!(condition)) { \
+        fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+        failures++; \
+    } \
+} while (0)
+
+/* outputs 999, 1000 or 1001 as the input is below, equal to or above 8 */
+static const int32_t COMPARE[] = {
+    3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,
+    1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,
+    1105,1,46,98,99,
+};
+
+static void test_io(void) {
+    intcode_computer *computer = intcode_new(COMPARE, sizeof COMPARE / sizeof *COMPARE);
+    int32_t value = 0;
+
+    CHECK(intcode_run(computer) == INTCODE_INPUT);
+    CHECK(intcode_pop_output(computer, &value) == INTCODE_EMPTY);
+
+    intcode_push_input(computer, 8);
+
+    CHECK(intcode_run(computer) == INTCODE_HALT);
+    CHECK(intcode_pop_output(computer, &value) == INTCODE_OK);
+    CHECK(value == 1000);
+    CHECK(intcode_pop_output(computer, &value) == INTCODE_EMPTY);
+
+    intcode_free(computer);
+}
+
+static void test_memory(void) {
+    const int32_t program[] = {1, 0, 0, 0, 99};
+    intcode_computer *computer = intcode_new(program, 5);
+    int32_t value = 0;
+
+    CHECK(intcode_memory_size(computer) == 5);
+
+    /* turn the ADD into a MUL before running */
+    CHECK(intcode_write(computer, 0, 2) == INTCODE_OK);
+    CHECK(intcode_write(computer, 5, 0) == INTCODE_OUT_OF_RANGE);
+    CHECK(intcode_run(computer) == INTCODE_HALT);
+
+    CHECK(intcode_read(computer, 0, &value) == INTCODE_OK);
+    CHECK(value == 4);
+    CHECK(intcode_read(computer, 5, &value) == INTCODE_OUT_OF_RANGE);
+
+    intcode_free(computer);
+}
+
+static void test_fault(void) {
+    const int32_t program[] = {77};
+    intcode_computer *computer = intcode_new(program, 1);
+
+    CHECK(intcode_run(computer) == INTCODE_FAULT);
+
+    intcode_free(computer);
+    intcode_free(NULL);
+}
+
+int main(void) {
+    test_io();
+    test_memory();
+    test_fault();
+
+    if (failures) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return EXIT_FAILURE;
+    }
+
+    printf("all checks passed\n");
+    return EXIT_SUCCESS;
+}
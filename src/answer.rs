@@ -0,0 +1,148 @@
+// the answer to a puzzle part
+//
+// answers are serialized on a single line so they can be stored in the
+// expected answers file: integers as they are, strings quoted as in JSON, and
+// multi-line text as a JSON array of its lines, e.g.
+//
+//     3427972
+//     "CJZLP"
+//     [".##..", "#..#.", "#...."]
+use std::fmt;
+
+use advent_of_code_2019::json::{self, Value};
+
+#[derive(Clone, Debug)]
+pub enum Answer {
+    SIGNED(i64),
+    UNSIGNED(u64),
+    STRING(String),
+    // one string per line, such as a grid of rendered letters
+    TEXT(Vec<String>),
+}
+
+impl Answer {
+    fn as_integer(&self) -> Option<i128> {
+        match self {
+            Self::SIGNED(value) => Some(*value as i128),
+            Self::UNSIGNED(value) => Some(*value as i128),
+            _ => None,
+        }
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Self::TEXT(_))
+    }
+
+    pub fn serialize(&self) -> String {
+        match self {
+            Self::SIGNED(value) => value.to_string(),
+            Self::UNSIGNED(value) => value.to_string(),
+            Self::STRING(value) => Value::from(value.as_str()).to_string(),
+            Self::TEXT(lines) => {
+                let lines = lines.iter()
+                    .map(|line| Value::from(line.as_str()))
+                    .collect::<Vec<Value>>();
+
+                Value::from(lines).to_string()
+            },
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+
+        if text.starts_with('"') || text.starts_with('[') {
+            return match json::parse(text)? {
+                Value::STRING(value) => Ok(Self::STRING(value)),
+                Value::ARRAY(values) => {
+                    values.into_iter()
+                        .map(|value| match value {
+                            Value::STRING(line) => Ok(line),
+                            _ => Err(format!("text lines must be strings: {}", text)),
+                        })
+                        .collect::<Result<Vec<String>, String>>()
+                        .map(Self::TEXT)
+                },
+                _ => Err(format!("invalid answer: {}", text)),
+            };
+        }
+
+        if let Ok(value) = text.parse::<i64>() {
+            return Ok(Self::SIGNED(value));
+        }
+
+        text.parse::<u64>()
+            .map(Self::UNSIGNED)
+            .map_err(|_| format!("invalid answer: {}", text))
+    }
+}
+
+// integers are equal whatever their signedness, so an answer computed as a
+// count matches one parsed from the expected answers
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::STRING(a), Self::STRING(b)) => a == b,
+            (Self::TEXT(a), Self::TEXT(b)) => a == b,
+            _ => match (self.as_integer(), other.as_integer()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SIGNED(value) => write!(f, "{}", value),
+            Self::UNSIGNED(value) => write!(f, "{}", value),
+            Self::STRING(value) => write!(f, "{}", value),
+            Self::TEXT(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Self::SIGNED(value as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Self::SIGNED(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Self::UNSIGNED(value as u64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Self::UNSIGNED(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Self::UNSIGNED(value as u64)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::STRING(value.to_string())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::STRING(value)
+    }
+}
@@ -0,0 +1,532 @@
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use crate::intcode::program::{self, Format};
+use crate::intcode::conformance::{self, Engine};
+use crate::intcode::gateway::Gateway;
+use crate::intcode::network::{Network, Traffic};
+use crate::intcode::{
+    compiler, dap, decompile, Arithmetic, Computer, CostModel, Fault, Heatmap, Patch, Signal,
+};
+
+const USAGE: &str = "\
+usage: intcode run <program> [options] [inputs...]
+       intcode dap [--port <port>]
+       intcode decompile <program> [--patch <edit>...] [--patch-file <file>...]
+       intcode compile <source> [-o <output>] [--binary]
+       intcode convert <program> <output> [--binary]
+       intcode conformance [<directory>]
+       intcode network <program> [--nodes <n>] [--rounds <n>] [--port <port>]
+
+run options:
+    --stdin             read comma or whitespace separated inputs from stdin
+    --stdin-ascii       read inputs from stdin as ASCII text
+    --ascii             print outputs as ASCII text instead of numbers
+    --steps <n>         stop after executing n instructions
+    --timeout <ms>      stop after running for ms milliseconds
+    --gas <n>           stop before the total instruction cost exceeds n
+    --cost <op=n>       set the cost of an instruction such as MUL=3; every
+                        instruction costs 1 by default (repeatable)
+    --arithmetic <policy>
+                        what ADD and MUL do when a result does not fit in a
+                        word: wrapping, checked (the default, which faults)
+                        or saturating; the first overflow is printed to
+                        stderr
+    --patch <edit>      apply a patch file line such as 'addr=val' or
+                        'addr: old -> new' before running (repeatable)
+    --patch-file <file> apply every edit in a patch file (repeatable)
+    --heatmap <file>    write a memory access heatmap (.svg or .ppm) and
+                        print an access summary to stderr
+
+dap options:
+    --port <port>       listen on a localhost port instead of stdio
+
+network options:
+    --nodes <n>         number of nodes, addressed from 0 (default 50)
+    --rounds <n>        stop after n rounds
+    --port <port>       accept packets from and report traffic to localhost
+                        TCP clients; without it the network stops once it is
+                        idle for good or the NAT sends the same Y twice";
+
+pub fn intcode(args: &[String]) -> i32 {
+    match args.first().map(String::as_str) {
+        Some("run") => report(run(&args[1..])),
+        Some("dap") => report(debug(&args[1..])),
+        Some("decompile") => report(decompile(&args[1..])),
+        Some("compile") => report(compile(&args[1..])),
+        Some("convert") => report(convert(&args[1..])),
+        Some("conformance") => report(conformance(&args[1..])),
+        Some("network") => report(network(&args[1..])),
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        },
+    }
+}
+
+fn report(result: Result<i32, String>) -> i32 {
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            1
+        },
+    }
+}
+
+#[derive(Default)]
+struct RunOptions {
+    program: Option<String>,
+    inputs: Vec<i32>,
+    stdin: bool,
+    stdin_ascii: bool,
+    ascii: bool,
+    steps: Option<u64>,
+    timeout: Option<u64>,
+    gas: Option<u64>,
+    costs: Vec<String>,
+    arithmetic: Arithmetic,
+    patch: Patch,
+    heatmap: Option<String>,
+}
+
+// handles --patch and --patch-file, returning false for any other flag
+fn parse_patch(
+    arg: &str,
+    args: &mut std::slice::Iter<String>,
+    patch: &mut Patch
+) -> Result<bool, String> {
+    match arg {
+        "--patch" => {
+            let value = args.next()
+                .ok_or("--patch requires a value")?;
+            patch.edits.push(value.parse()?);
+        },
+        "--patch-file" => {
+            let path = args.next()
+                .ok_or("--patch-file requires a value")?;
+            let file = Patch::load(path)
+                .map_err(|e| format!("failed to load {}: {}", path, e))?;
+            patch.edits.extend(file.edits);
+        },
+        _ => return Ok(false),
+    }
+
+    Ok(true)
+}
+
+fn parse_run_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if parse_patch(arg, &mut args, &mut options.patch)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--stdin" => options.stdin = true,
+            "--stdin-ascii" => options.stdin_ascii = true,
+            "--ascii" => options.ascii = true,
+            "--steps" => {
+                let value = args.next()
+                    .ok_or("--steps requires a value")?;
+                let value = value.parse::<u64>()
+                    .map_err(|_| format!("invalid step limit: {}", value))?;
+                options.steps = Some(value);
+            },
+            "--timeout" => {
+                let value = args.next()
+                    .ok_or("--timeout requires a value")?;
+                let value = value.parse::<u64>()
+                    .map_err(|_| format!("invalid timeout: {}", value))?;
+                options.timeout = Some(value);
+            },
+            "--gas" => {
+                let value = args.next()
+                    .ok_or("--gas requires a value")?;
+                let value = value.parse::<u64>()
+                    .map_err(|_| format!("invalid gas limit: {}", value))?;
+                options.gas = Some(value);
+            },
+            "--cost" => {
+                let value = args.next()
+                    .ok_or("--cost requires a value")?;
+                options.costs.push(value.clone());
+            },
+            "--arithmetic" => {
+                let value = args.next()
+                    .ok_or("--arithmetic requires a value")?;
+                options.arithmetic = value.parse()?;
+            },
+            "--heatmap" => {
+                let value = args.next()
+                    .ok_or("--heatmap requires a value")?;
+                options.heatmap = Some(value.clone());
+            },
+            _ if options.program.is_none() => {
+                options.program = Some(arg.clone());
+            },
+            _ => {
+                let value = arg.parse::<i32>()
+                    .map_err(|_| format!("invalid input: {}", arg))?;
+                options.inputs.push(value);
+            },
+        }
+    }
+
+    if options.stdin && options.stdin_ascii {
+        return Err("--stdin and --stdin-ascii are exclusive".to_string());
+    }
+
+    Ok(options)
+}
+
+fn read_stdin_inputs(ascii: bool) -> Result<Vec<i32>, String> {
+    let mut text = String::new();
+
+    io::stdin().read_to_string(&mut text)
+        .map_err(|e| format!("failed to read stdin: {}", e))?;
+
+    if ascii {
+        return Ok(text.chars().map(|c| c as i32).collect());
+    }
+
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.parse::<i32>()
+                .map_err(|_| format!("invalid input on stdin: {}", item))
+        })
+        .collect()
+}
+
+fn print_outputs(computer: &mut Computer, ascii: bool) {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for value in computer.output_buffer.drain(..) {
+        match char::from_u32(value as u32) {
+            Some(c) if ascii && c.is_ascii() => write!(stdout, "{}", c),
+            _ => writeln!(stdout, "{}", value),
+        }.unwrap();
+    }
+
+    stdout.flush().unwrap();
+}
+
+const HEATMAP_COLUMNS: usize = 64;
+const HEATMAP_SCALE: usize = 8;
+
+fn write_heatmap(path: &str, heatmap: &Heatmap) -> Result<(), String> {
+    let image = match path.ends_with(".svg") {
+        true => heatmap.render_svg(HEATMAP_COLUMNS, HEATMAP_SCALE).into_bytes(),
+        false => heatmap.render_ppm(HEATMAP_COLUMNS, HEATMAP_SCALE),
+    };
+
+    std::fs::write(path, image)
+        .map_err(|e| format!("failed to write {}: {}", path, e))
+}
+
+fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_run_options(args)?;
+
+    let path = options.program.as_ref()
+        .ok_or_else(|| USAGE.to_string())?;
+
+    let program = program::load(path)
+        .map_err(|e| format!("failed to load {}: {}", path, e))?;
+
+    let mut computer = Computer::new(&program);
+
+    computer.with_patch(&options.patch)
+        .map_err(|e| e.to_string())?;
+
+    computer.input_buffer.extend(options.inputs.iter());
+
+    if options.stdin || options.stdin_ascii {
+        let inputs = read_stdin_inputs(options.stdin_ascii)?;
+        computer.input_buffer.extend(inputs);
+    }
+
+    computer.with_step_limit(options.steps);
+    computer.with_time_budget(options.timeout.map(Duration::from_millis));
+
+    let mut cost_model = CostModel::default();
+
+    for spec in options.costs.iter() {
+        cost_model.set_mnemonic(spec, computer.instruction_set())?;
+    }
+
+    computer.with_cost_model(cost_model);
+    computer.with_gas_limit(options.gas);
+    computer.with_arithmetic(options.arithmetic);
+
+    if options.heatmap.is_some() {
+        computer.instrument();
+    }
+
+    let result = computer.run();
+
+    print_outputs(&mut computer, options.ascii);
+
+    if let (Some(path), Some(heatmap)) = (&options.heatmap, computer.heatmap()) {
+        write_heatmap(path, heatmap)?;
+        eprint!("{}", heatmap.summary(10));
+    }
+
+    if options.gas.is_some() || !options.costs.is_empty() {
+        eprintln!("gas used: {}", computer.gas_used());
+    }
+
+    // a checked overflow is already reported as the fault which stopped it
+    if let Some(overflow) = computer.first_overflow() {
+        if !matches!(result, Err(Fault::OVERFLOW(_))) {
+            eprintln!("first {}", overflow);
+        }
+    }
+
+    match result {
+        Ok(Signal::HALT) => Ok(0),
+        Ok(Signal::INPUT) => Err(format!(
+            "program is waiting for input at address {} after {} steps",
+            computer.instruction_pointer(), computer.steps(),
+        )),
+        Ok(Signal::LIMIT) => Err(format!(
+            "step limit reached at address {} after {} steps",
+            computer.instruction_pointer(), computer.steps(),
+        )),
+        Ok(Signal::CANCELLED) => Err(format!(
+            "cancelled at address {} after {} steps",
+            computer.instruction_pointer(), computer.steps(),
+        )),
+        Ok(Signal::GAS) => Err(format!(
+            "out of gas at address {} after using {} gas",
+            computer.instruction_pointer(), computer.gas_used(),
+        )),
+        Ok(Signal::TIMEOUT) => Err(format!(
+            "timed out at address {} after {} steps",
+            computer.instruction_pointer(), computer.steps(),
+        )),
+        Err(fault) => Err(format!("{}", computer.crash_report(&fault))),
+    }
+}
+
+fn debug(args: &[String]) -> Result<i32, String> {
+    let result = match args {
+        [] => dap::serve_stdio(),
+        [flag, port] if flag == "--port" => {
+            let port = port.parse::<u16>()
+                .map_err(|_| format!("invalid port: {}", port))?;
+            dap::serve_tcp(port)
+        },
+        _ => return Err(USAGE.to_string()),
+    };
+
+    result.map(|_| 0).map_err(|e| format!("debug adapter failed: {}", e))
+}
+
+fn decompile(args: &[String]) -> Result<i32, String> {
+    let mut path = None;
+    let mut patch = Patch::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if parse_patch(arg, &mut args, &mut patch)? {
+            continue;
+        }
+
+        if path.is_some() {
+            return Err(USAGE.to_string());
+        }
+
+        path = Some(arg);
+    }
+
+    let path = path.ok_or_else(|| USAGE.to_string())?;
+
+    let program = program::load(path)
+        .map_err(|e| format!("failed to load {}: {}", path, e))?;
+
+    let mut computer = Computer::new(&program);
+
+    computer.with_patch(&patch)
+        .map_err(|e| e.to_string())?;
+
+    print!("{}", decompile::decompile(computer.memory(), computer.instruction_set()));
+
+    Ok(0)
+}
+
+fn compile(args: &[String]) -> Result<i32, String> {
+    let mut path = None;
+    let mut output = None;
+    let mut binary = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => output = Some(args.next().ok_or("-o requires a value")?),
+            "--binary" => binary = true,
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let path = path.ok_or_else(|| USAGE.to_string())?;
+
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to load {}: {}", path, e))?;
+
+    let program = compiler::compile(&source)
+        .map_err(|e| format!("{}:{}", path, e))?;
+
+    match output {
+        Some(output) => save(output, &program, binary)?,
+        None if binary => return Err("--binary requires -o".to_string()),
+        None => println!("{}", program::to_text(&program)),
+    }
+
+    Ok(0)
+}
+
+fn save(path: &str, words: &[i32], binary: bool) -> Result<(), String> {
+    let format = match binary {
+        true => Format::BINARY,
+        false => Format::TEXT,
+    };
+
+    program::save(path, words, format)
+        .map_err(|e| format!("failed to write {}: {}", path, e))
+}
+
+// converts between the text and binary formats; the input format is detected
+fn convert(args: &[String]) -> Result<i32, String> {
+    let (input, output, binary) = match args {
+        [input, output] => (input, output, false),
+        [input, output, flag] if flag == "--binary" => (input, output, true),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let words = program::load(input)
+        .map_err(|e| format!("failed to load {}: {}", input, e))?;
+
+    save(output, &words, binary)?;
+
+    Ok(0)
+}
+
+// runs every case in the conformance suite, exiting with 1 if any failed
+fn conformance(args: &[String]) -> Result<i32, String> {
+    let directory = match args {
+        [] => "conformance",
+        [directory] => directory.as_str(),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let cases = conformance::load_suite(directory)
+        .map_err(|e| format!("failed to load {}: {}", directory, e))?;
+
+    let engine = conformance::Interpreter;
+    let results = conformance::run_suite(&cases, &engine);
+
+    for result in results.iter() {
+        match result.passed() {
+            true => println!("PASS {}", result.name),
+            false => println!("FAIL {}: {}", result.name, result.failures.join("; ")),
+        }
+    }
+
+    let failed = results.iter().filter(|result| !result.passed()).count();
+
+    println!(
+        "{}: {} passed, {} failed",
+        engine.name(), results.len() - failed, failed,
+    );
+
+    match failed {
+        0 => Ok(0),
+        _ => Ok(1),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value", flag))?;
+    value.parse::<T>().map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+fn network(args: &[String]) -> Result<i32, String> {
+    let mut path = None;
+    let mut nodes = 50;
+    let mut rounds = None;
+    let mut port = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--nodes" => nodes = parse_number(arg, args.next())?,
+            "--rounds" => rounds = Some(parse_number::<u64>(arg, args.next())?),
+            "--port" => port = Some(parse_number::<u16>(arg, args.next())?),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let path = path.ok_or_else(|| USAGE.to_string())?;
+
+    let program = program::load(path)
+        .map_err(|e| format!("failed to load {}: {}", path, e))?;
+
+    let gateway = match port {
+        Some(port) => {
+            let gateway = Gateway::bind(port)
+                .map_err(|e| format!("failed to listen on port {}: {}", port, e))?;
+            eprintln!("gateway listening on {}", gateway.address());
+            Some(gateway)
+        },
+        None => None,
+    };
+
+    let mut network = Network::new(&program, nodes);
+    let mut round = 0;
+
+    while rounds.is_none_or(|rounds| round < rounds) && !network.is_halted() {
+        round += 1;
+
+        let mut traffic = Vec::new();
+
+        if let Some(gateway) = gateway.as_ref() {
+            for packet in gateway.poll() {
+                traffic.push(network.inject(packet));
+            }
+        }
+
+        traffic.extend(network.round());
+
+        for traffic in traffic.iter() {
+            println!("{}", traffic);
+
+            if let Some(gateway) = gateway.as_ref() {
+                gateway.broadcast(traffic);
+            }
+        }
+
+        let woke = traffic.iter().any(|traffic| matches!(traffic, Traffic::WAKE(_)));
+
+        let repeated = match network.nat().woken[..] {
+            [.., previous, last] => woke && previous.y == last.y,
+            _ => false,
+        };
+
+        match gateway {
+            // nothing outside the network can wake it up again
+            None if network.is_idle() || repeated => break,
+            Some(_) if network.is_idle() => {
+                std::thread::sleep(Duration::from_millis(10));
+            },
+            _ => (),
+        }
+    }
+
+    Ok(0)
+}
@@ -1,6 +1,8 @@
 // https://adventofcode.com/2019/day/1
 
-pub fn part1() -> i32 {
+use crate::solution::Solution;
+
+fn part1(input: &str) -> i32 {
     struct Module {
         mass: i32,
     }
@@ -15,16 +17,10 @@ pub fn part1() -> i32 {
     assert_eq!(654, Module::new(1969).fuel_required());
     assert_eq!(33583, Module::new(100756).fuel_required());
 
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
-
-    let handle = File::open("input/day01/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
-
     let mut total_fuel = 0;
 
-    for line in buffer.lines() {
-        let mass = line.unwrap().parse::<i32>().unwrap();
+    for line in input.lines() {
+        let mass = line.parse::<i32>().unwrap();
         let module = Module::new(mass);
         total_fuel += module.fuel_required();
     }
@@ -32,7 +28,7 @@ pub fn part1() -> i32 {
     total_fuel
 }
 
-pub fn part2() -> i32 {
+fn part2(input: &str) -> i32 {
     struct Module {
         mass: i32,
     }
@@ -65,19 +61,23 @@ pub fn part2() -> i32 {
     assert_eq!(966, Module::new(1969).fuel_required());
     assert_eq!(50346, Module::new(100756).fuel_required());
 
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
-
-    let handle = File::open("input/day01/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
-
     let mut total_fuel = 0;
 
-    for line in buffer.lines() {
-        let mass = line.unwrap().parse::<i32>().unwrap();
+    for line in input.lines() {
+        let mass = line.parse::<i32>().unwrap();
         let module = Module::new(mass);
         total_fuel += module.fuel_required();
     }
 
     total_fuel
-}
\ No newline at end of file
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn part1(input: &str) -> i32 { part1(input) }
+    fn part2(input: &str) -> i32 { part2(input) }
+}
@@ -1,99 +1,39 @@
 // https://adventofcode.com/2019/day/2
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::intcode::{program, Batch, Computer};
+use crate::solution::Solution;
 
-use crate::intcode::Computer;
+fn part1(input: &str) -> i32 {
+    let mut computer = Computer::new(&program::parse(input).unwrap());
 
-pub fn part1() -> i32 {
-    assert_eq!(
-        [2,0,0,0,99].to_vec(),
-        Computer::new(&[1,0,0,0,99]).execute_program().memory
-    );
-
-    assert_eq!(
-        [2,3,0,6,99].to_vec(),
-        Computer::new(&[2,3,0,3,99]).execute_program().memory
-    );
-
-    assert_eq!(
-        [2,4,4,5,99,9801].to_vec(),
-        Computer::new(&[2,4,4,5,99,0]).execute_program().memory
-    );
-
-    assert_eq!(
-        [30,1,1,4,2,5,6,0,99].to_vec(),
-        Computer::new(&[1,1,1,4,99,5,6,0,99]).execute_program().memory
-    );
-
-    let handle = File::open("input/day02/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
-
-    let computers = buffer.lines()
-        .map(|line| {
-            line.unwrap().split(',').map(|item| {
-                item.parse::<i32>().unwrap()
-            }).collect::<Vec<i32>>()
-        })
-        .map(|program| {
-            Computer::new(&program)
-        })
-        .collect::<Vec<Computer>>();
-
-    let mut computer = computers[0].clone();
-
-    computer.with_inputs(12, 2).execute_program().memory[0]
+    computer.with_inputs(12, 2).execute_program().memory()[0]
 }
 
-pub fn part2() -> i32 {
-    assert_eq!(
-        [2,0,0,0,99].to_vec(),
-        Computer::new(&[1,0,0,0,99]).execute_program().memory
-    );
+fn part2(input: &str) -> i32 {
+    let computer = Computer::new(&program::parse(input).unwrap());
 
-    assert_eq!(
-        [2,3,0,6,99].to_vec(),
-        Computer::new(&[2,3,0,3,99]).execute_program().memory
-    );
+    let trials = (0..99).flat_map(|noun| (0..99).map(move |verb| (noun, verb)));
 
-    assert_eq!(
-        [2,4,4,5,99,9801].to_vec(),
-        Computer::new(&[2,4,4,5,99,0]).execute_program().memory
+    let found = Batch::new(&computer).find(
+        trials,
+        |computer, &(noun, verb)| {
+            computer.with_inputs(noun, verb).execute_program().memory()[0]
+        },
+        |&result| result == 19690720,
     );
 
-    assert_eq!(
-        [30,1,1,4,2,5,6,0,99].to_vec(),
-        Computer::new(&[1,1,1,4,99,5,6,0,99]).execute_program().memory
-    );
-
-    let handle = File::open("input/day02/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
-
-    let computers = buffer.lines()
-        .map(|line| {
-            line.unwrap().split(',').map(|item| {
-                item.parse::<i32>().unwrap()
-            }).collect::<Vec<i32>>()
-        })
-        .map(|program| {
-            Computer::new(&program)
-        })
-        .collect::<Vec<Computer>>();
+    match found {
+        Some(((noun, verb), _)) => 100 * noun + verb,
+        None => 0,
+    }
+}
 
-    let computer = computers[0].clone();
+pub struct Day02;
 
-    for noun in 0..99 {
-        for verb in 0..99 {
-            let result = computer.clone()
-                .with_inputs(noun, verb)
-                .execute_program()
-                .memory[0];
+impl Solution for Day02 {
+    type Part1 = i32;
+    type Part2 = i32;
 
-            if result == 19690720 {
-                return 100 * noun + verb;
-            }
-        }
-    }
-
-    0
-}
\ No newline at end of file
+    fn part1(input: &str) -> i32 { part1(input) }
+    fn part2(input: &str) -> i32 { part2(input) }
+}
@@ -1,7 +1,6 @@
 // https://adventofcode.com/2019/day/3
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::solution::Solution;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 struct Point(i32, i32);
@@ -135,7 +134,7 @@ fn manhattan_distance(a: &Point, b: &Point) -> i32 {
     (a.0 - b.0).abs() + (a.1 - b.1).abs()
 }
 
-pub fn part1() -> i32 {
+fn part1(input: &str) -> i32 {
     let o = Point(0, 0);
 
     assert_eq!(
@@ -168,28 +167,25 @@ pub fn part1() -> i32 {
             .min().unwrap()
     );
 
-    let handle = File::open("input/day03/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
-
-    let wires = buffer.lines()
-        .map(|line| Wire::from(line.unwrap().as_str()))
+    let wires = input.lines()
+        .map(Wire::from)
         .collect::<Vec<Wire>>();
 
     let (wire1, wire2) = (&wires[0], &wires[1]);
 
     find_crossings(
-        &wire1,
-        &wire2,
+        wire1,
+        wire2,
     ).into_iter()
         .map(|(_, _, p)| manhattan_distance(&o, &p))
         .min().unwrap()
 }
 
-pub fn part2() -> i32 {
+fn part2(input: &str) -> i32 {
     fn f(wire1: &Wire, wire2: &Wire) -> i32 {
         find_crossings(
-            &wire1,
-            &wire2,
+            wire1,
+            wire2,
         ).into_iter()
             .map(|(idx1, idx2, p)| {
                 let vertex_a = &wire1.vertices[idx1];
@@ -218,11 +214,8 @@ pub fn part2() -> i32 {
     let wire2 = Wire::from("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7");
     assert_eq!(410, f(&wire1, &wire2));
 
-    let handle = File::open("input/day03/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
-
-    let wires = buffer.lines()
-        .map(|line| Wire::from(line.unwrap().as_str()))
+    let wires = input.lines()
+        .map(Wire::from)
         .collect::<Vec<Wire>>();
 
     let (wire1, wire2) = (&wires[0], &wires[1]);
@@ -266,4 +259,14 @@ pub fn part2() -> i32 {
     // }
 
     f(wire1, wire2)
-}
\ No newline at end of file
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn part1(input: &str) -> i32 { part1(input) }
+    fn part2(input: &str) -> i32 { part2(input) }
+}
@@ -1,7 +1,6 @@
 // https://adventofcode.com/2019/day/4
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::solution::Solution;
 
 #[derive(Debug)]
 struct PasswordCriteria {
@@ -138,7 +137,7 @@ impl PasswordCriteria {
     }
 }
 
-pub fn part1() -> i32 {
+fn part1(input: &str) -> usize {
     assert_eq!(
         true,
         PasswordCriteria::new(0, 999999).check_part1(111111)
@@ -154,13 +153,7 @@ pub fn part1() -> i32 {
         PasswordCriteria::new(0, 999999).check_part1(123789)
     );
 
-    let handle = File::open("input/day04/input.txt").unwrap();
-    let mut buffer = BufReader::new(handle);
-
-    let mut line = String::new();
-    buffer.read_line(&mut line).unwrap();
-
-    let items = line.split("-")
+    let items = input.trim().split('-')
         .map(|item| item.parse::<i32>().unwrap())
         .collect::<Vec<i32>>();
 
@@ -168,14 +161,12 @@ pub fn part1() -> i32 {
 
     let criteria = PasswordCriteria::new(min_val, max_val);
 
-    let result = (min_val..max_val)
+    (min_val..max_val)
         .filter(|&password| criteria.check_part1(password))
-        .count();
-
-    result as i32
+        .count()
 }
 
-pub fn part2() -> i32 {
+fn part2(input: &str) -> usize {
     assert_eq!(
         true,
         PasswordCriteria::new(0, 999999).check_part2(112233)
@@ -191,13 +182,7 @@ pub fn part2() -> i32 {
         PasswordCriteria::new(0, 999999).check_part2(111122)
     );
 
-    let handle = File::open("input/day04/input.txt").unwrap();
-    let mut buffer = BufReader::new(handle);
-
-    let mut line = String::new();
-    buffer.read_line(&mut line).unwrap();
-
-    let items = line.split("-")
+    let items = input.trim().split('-')
         .map(|item| item.parse::<i32>().unwrap())
         .collect::<Vec<i32>>();
 
@@ -205,9 +190,17 @@ pub fn part2() -> i32 {
 
     let criteria = PasswordCriteria::new(min_val, max_val);
 
-    let result = (min_val..max_val)
+    (min_val..max_val)
         .filter(|&password| criteria.check_part2(password))
-        .count();
+        .count()
+}
+
+pub struct Day04;
 
-    result as i32
-}
\ No newline at end of file
+impl Solution for Day04 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part1(input: &str) -> usize { part1(input) }
+    fn part2(input: &str) -> usize { part2(input) }
+}
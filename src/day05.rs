@@ -1,38 +1,133 @@
 // https://adventofcode.com/2019/day/5
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt;
 
-use crate::intcode::Computer;
+use crate::intcode::{program, Computer, Fault};
+use crate::solution::Solution;
 
-fn load() -> Computer {
-    let handle = File::open("input/day05/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
+fn load(input: &str) -> Computer {
+    Computer::new(&program::parse(input).unwrap())
+}
 
-    let computers = buffer.lines()
-        .map(|line| {
-            line.unwrap().split(',').map(|item| {
-                item.parse::<i32>().unwrap()
-            }).collect::<Vec<i32>>()
-        })
-        .map(|program| {
-            Computer::new(&program)
+// a single output emitted by the diagnostic program, along with the address
+// of the OUTPUT instruction that produced it
+#[derive(Debug)]
+pub struct DiagnosticOutput {
+    pub address: usize,
+    pub value: i32,
+}
+
+// every output except the last is a test result which is zero when the check
+// passed; the last output is the diagnostic code itself
+#[derive(Debug)]
+pub struct TestResult {
+    pub output: DiagnosticOutput,
+    pub passed: bool,
+}
+
+#[derive(Debug)]
+pub struct DiagnosticReport {
+    pub tests: Vec<TestResult>,
+    pub code: Option<DiagnosticOutput>,
+}
+
+#[derive(Debug)]
+pub enum DiagnosticError {
+    FAILED { index: usize, address: usize, value: i32 },
+    MISSING,
+}
+
+impl fmt::Display for DiagnosticError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FAILED { index, address, value } => write!(
+                f,
+                "diagnostic test #{} (OUTPUT at address {}) failed with {}",
+                index, address, value,
+            ),
+            Self::MISSING => write!(
+                f,
+                "diagnostic program halted without a diagnostic code",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiagnosticError {}
+
+impl DiagnosticReport {
+    // returns the diagnostic code, or the first test which did not pass
+    pub fn verify(&self) -> Result<i32, DiagnosticError> {
+        let failure = self.tests.iter()
+            .enumerate()
+            .find(|(_, test)| !test.passed);
+
+        if let Some((index, test)) = failure {
+            return Err(DiagnosticError::FAILED {
+                index,
+                address: test.output.address,
+                value: test.output.value,
+            });
+        }
+
+        match &self.code {
+            Some(output) => Ok(output.value),
+            None => Err(DiagnosticError::MISSING),
+        }
+    }
+}
+
+pub fn run_diagnostic(
+    computer: &mut Computer,
+    system_id: i32
+) -> Result<DiagnosticReport, Fault> {
+    computer.input_buffer.push_back(system_id);
+
+    let mut outputs = Vec::new();
+
+    loop {
+        let address = computer.instruction_pointer();
+        let signal = computer.step()?;
+
+        while let Some(value) = computer.output_buffer.pop_front() {
+            outputs.push(DiagnosticOutput { address, value });
+        }
+
+        if signal.is_some() {
+            break;
+        }
+    }
+
+    let code = outputs.pop();
+
+    let tests = outputs.into_iter()
+        .map(|output| {
+            let passed = output.value == 0;
+            TestResult { output, passed }
         })
-        .collect::<Vec<Computer>>();
+        .collect();
+
+    Ok(DiagnosticReport { tests, code })
+}
 
-    computers[0].clone()
+fn part1(input: &str) -> i32 {
+    let mut computer = load(input);
+    let report = run_diagnostic(&mut computer, 1).unwrap();
+    report.verify().unwrap()
 }
 
-pub fn part1() -> i32 {
-    let mut computer = load();
-    computer.input_buffer.push_back(1);
-    computer.execute_program();
-    computer.output_buffer.pop_back().unwrap()
+fn part2(input: &str) -> i32 {
+    let mut computer = load(input);
+    let report = run_diagnostic(&mut computer, 5).unwrap();
+    report.verify().unwrap()
 }
 
-pub fn part2() -> i32 {
-    let mut computer = load();
-    computer.input_buffer.push_back(5);
-    computer.execute_program();
-    computer.output_buffer.pop_back().unwrap()
-}
\ No newline at end of file
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn part1(input: &str) -> i32 { part1(input) }
+    fn part2(input: &str) -> i32 { part2(input) }
+}
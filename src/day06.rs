@@ -1,10 +1,9 @@
 // https://adventofcode.com/2019/day/6
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
 use std::collections::{HashMap, VecDeque};
 
+use crate::solution::Solution;
+
 #[derive(Debug)]
 struct Node<T> {
     data: T,
@@ -18,15 +17,11 @@ impl<T> Node<T> {
     }
 }
 
-fn load() -> (Vec<Node<String>>, HashMap<String, usize>) {
-    let handle = File::open("input/day06/input.txt").unwrap();
-    let line_buffer = BufReader::new(handle);
-
+fn load(input: &str) -> (Vec<Node<String>>, HashMap<String, usize>) {
     let mut node_buffer = Vec::new();
     let mut node_mapping = HashMap::new();
 
-    for line in line_buffer.lines() {
-        let line = line.unwrap();
+    for line in input.lines() {
         let mut parts = line.split(')');
 
         let pkey = parts.next().unwrap().to_string();
@@ -68,8 +63,8 @@ fn load() -> (Vec<Node<String>>, HashMap<String, usize>) {
 //---------------------------------------------------------------
 //     0       1       5      11      19      29      35      42
 
-pub fn part1() -> i32 {
-    let (node_buffer, node_mapping) = load();
+fn part1(input: &str) -> usize {
+    let (node_buffer, _) = load(input);
 
     let root_node = node_buffer.iter()
         .find(|node| node.parent.is_none())
@@ -88,8 +83,8 @@ pub fn part1() -> i32 {
     total
 }
 
-pub fn part2() -> i32 {
-    let (node_buffer, node_mapping) = load();
+fn part2(input: &str) -> usize {
+    let (node_buffer, node_mapping) = load(input);
 
     let node_you = &node_buffer[node_mapping["YOU"]];
     let node_san = &node_buffer[node_mapping["SAN"]];
@@ -117,7 +112,15 @@ pub fn part2() -> i32 {
         }
     }
 
-    let num_transfers = iter_you.count() + iter_san.count();
+    iter_you.count() + iter_san.count()
+}
 
-    num_transfers as i32
-}
\ No newline at end of file
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part1(input: &str) -> usize { part1(input) }
+    fn part2(input: &str) -> usize { part2(input) }
+}
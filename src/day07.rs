@@ -1,54 +1,47 @@
 // https://adventofcode.com/2019/day/7
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
 use itertools::Itertools;
 
-use crate::intcode::Computer;
-
-pub fn part1() -> i32 {
-    let handle = File::open("input/day07/input.txt").unwrap();
-    let buffer = BufReader::new(handle);
+use crate::intcode::{program, Batch, Computer};
+use crate::solution::Solution;
 
-    let program = buffer.lines()
-        .map(|line| {
-            line.unwrap().split(',').map(|item| {
-                item.parse::<i32>().unwrap()
-            }).collect::<Vec<i32>>()
-        }).next().unwrap();
+fn part1(input: &str) -> i32 {
+    let program = program::parse(input).unwrap();
 
     let num_phase_settings = 5;
     let phase_settings = 0..num_phase_settings;
 
-    let mut max_signal = 0;
-
-    for phases in phase_settings.permutations(num_phase_settings) {
-        let mut opamps = [
-            Computer::new(&program.clone()),
-            Computer::new(&program.clone()),
-            Computer::new(&program.clone()),
-            Computer::new(&program.clone()),
-            Computer::new(&program.clone()),
-        ];
+    let signals = Batch::new(&Computer::new(&program)).map(
+        phase_settings.permutations(num_phase_settings),
+        |computer, phases| {
+            let mut signal = 0;
 
-        let mut signal = 0;
+            for phase in phases.iter() {
+                let mut opamp = computer.clone();
 
-        for (phase, mut opamp) in phases.iter().zip(opamps.iter_mut()) {
-            opamp.input_buffer.push_back(*phase as i32);
-            opamp.input_buffer.push_back(signal);
+                opamp.input_buffer.push_back(*phase as i32);
+                opamp.input_buffer.push_back(signal);
 
-            opamp.execute_program();
+                signal = opamp.outputs().next().unwrap().unwrap();
+            }
 
-            signal = opamp.output_buffer.pop_back().unwrap();
-        }
+            signal
+        },
+    );
 
-        max_signal = std::cmp::max(signal, max_signal);
-    }
-
-    max_signal
+    signals.into_iter().max().unwrap()
 }
 
-pub fn part2() -> i32 {
+fn part2(_input: &str) -> i32 {
     0
-}
\ No newline at end of file
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn part1(input: &str) -> i32 { part1(input) }
+    fn part2(input: &str) -> i32 { part2(input) }
+}
@@ -0,0 +1,116 @@
+// the accepted answers, stored one per line as '<day> <part> <input> <answer>'
+// with '#' starting a comment line, and each answer serialized as described
+// in answer.rs
+//
+// answers are keyed by the puzzle input they were given for, so that every
+// teammate's input can have its own; <input> is the key printed by input_key
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::answer::Answer;
+
+pub const DEFAULT_PATH: &str = "answers.txt";
+
+// an FNV-1a hash of the input with surrounding whitespace trimmed, so that a
+// missing trailing newline does not change the key
+pub fn input_key(input: &str) -> String {
+    let hash = input.trim().bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{:016x}", hash)
+}
+
+// splits off the first whitespace separated field, leaving the rest of the
+// line as it is, since a serialized answer may itself contain spaces
+fn field(text: &str) -> Option<(&str, &str)> {
+    text.trim_start().split_once(char::is_whitespace)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    PASS,
+    FAIL { expected: Answer },
+    UNKNOWN,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::PASS => "pass",
+            Self::FAIL { .. } => "FAIL",
+            Self::UNKNOWN => "unknown",
+        };
+
+        f.pad(name)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Expected {
+    answers: HashMap<(u32, u32, String), Answer>,
+}
+
+impl Expected {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut answers = HashMap::new();
+        let mut lines = HashMap::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| format!("line {}: {}: {}", index + 1, message, line);
+
+            let fields = field(line).and_then(|(day, rest)| {
+                let (part, rest) = field(rest)?;
+                let (input, answer) = field(rest)?;
+                Some((day, part, input, answer))
+            });
+
+            let Some((day, part, input, answer)) = fields else {
+                return Err(error("expected '<day> <part> <input> <answer>'"));
+            };
+
+            let day = day.parse::<u32>().map_err(|_| error("invalid day"))?;
+            let part = part.parse::<u32>().map_err(|_| error("invalid part"))?;
+            let answer = Answer::parse(answer).map_err(|e| error(&e))?;
+
+            let key = (day, part, input.to_string());
+
+            if let Some(first) = lines.insert(key.clone(), index + 1) {
+                return Err(error(&format!("duplicate answer, first given on line {}", first)));
+            }
+
+            answers.insert(key, answer);
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Self::parse(&text))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u32, part: u32, input: &str) -> Option<&Answer> {
+        self.answers.get(&(day, part, input_key(input)))
+    }
+
+    pub fn check(&self, day: u32, part: u32, input: &str, answer: &Answer) -> Check {
+        match self.get(day, part, input) {
+            Some(expected) if expected == answer => Check::PASS,
+            Some(expected) => Check::FAIL { expected: expected.clone() },
+            None => Check::UNKNOWN,
+        }
+    }
+}
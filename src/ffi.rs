@@ -0,0 +1,118 @@
+// a C interface to the Intcode computer; build.rs generates intcode.h from
+// the declarations in this file, so keep to the types it knows how to map
+//
+// every function taking a computer expects a pointer returned by
+// intcode_new which has not yet been passed to intcode_free, and pointer
+// arguments for results must be valid for writes
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::slice;
+
+use crate::intcode::{Computer, Signal};
+
+pub const INTCODE_HALT: i32 = 0;
+pub const INTCODE_INPUT: i32 = 1;
+pub const INTCODE_LIMIT: i32 = 2;
+pub const INTCODE_CANCELLED: i32 = 3;
+pub const INTCODE_TIMEOUT: i32 = 4;
+pub const INTCODE_GAS: i32 = 5;
+pub const INTCODE_FAULT: i32 = -1;
+
+pub const INTCODE_OK: i32 = 0;
+pub const INTCODE_EMPTY: i32 = 1;
+pub const INTCODE_OUT_OF_RANGE: i32 = -1;
+
+// copies the program, which may be freed as soon as this returns
+#[no_mangle]
+pub unsafe extern "C" fn intcode_new(words: *const i32, length: usize) -> *mut Computer {
+    let program = match length {
+        0 => &[],
+        _ => slice::from_raw_parts(words, length),
+    };
+
+    Box::into_raw(Box::new(Computer::new(program)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn intcode_free(computer: *mut Computer) {
+    if !computer.is_null() {
+        drop(Box::from_raw(computer));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn intcode_push_input(computer: *mut Computer, value: i32) {
+    let computer = &mut *computer;
+    computer.input_buffer.push_back(value);
+}
+
+// runs until the program stops, returning one of the INTCODE_ status codes
+#[no_mangle]
+pub unsafe extern "C" fn intcode_run(computer: *mut Computer) -> i32 {
+    let computer = &mut *computer;
+
+    match computer.run() {
+        Ok(Signal::HALT) => INTCODE_HALT,
+        Ok(Signal::INPUT) => INTCODE_INPUT,
+        Ok(Signal::LIMIT) => INTCODE_LIMIT,
+        Ok(Signal::CANCELLED) => INTCODE_CANCELLED,
+        Ok(Signal::TIMEOUT) => INTCODE_TIMEOUT,
+        Ok(Signal::GAS) => INTCODE_GAS,
+        Err(_) => INTCODE_FAULT,
+    }
+}
+
+// writes the oldest output to value, or returns INTCODE_EMPTY
+#[no_mangle]
+pub unsafe extern "C" fn intcode_pop_output(computer: *mut Computer, value: *mut i32) -> i32 {
+    let computer = &mut *computer;
+
+    match computer.output_buffer.pop_front() {
+        Some(output) => {
+            *value = output;
+            INTCODE_OK
+        },
+        None => INTCODE_EMPTY,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn intcode_memory_size(computer: *const Computer) -> usize {
+    let computer = &*computer;
+    computer.memory().len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn intcode_read(
+    computer: *const Computer,
+    address: usize,
+    value: *mut i32
+) -> i32 {
+    let computer = &*computer;
+
+    match computer.memory().get(address) {
+        Some(word) => {
+            *value = *word;
+            INTCODE_OK
+        },
+        None => INTCODE_OUT_OF_RANGE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn intcode_write(
+    computer: *mut Computer,
+    address: usize,
+    value: i32
+) -> i32 {
+    let computer = &mut *computer;
+
+    if address >= computer.memory().len() {
+        return INTCODE_OUT_OF_RANGE;
+    }
+
+    computer.write(address, value);
+
+    INTCODE_OK
+}
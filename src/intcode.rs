@@ -1,12 +1,58 @@
 use std::collections::VecDeque;
-
-#[derive(Clone)]
-enum Signal {
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod arithmetic;
+mod batch;
+mod cancel;
+mod crash;
+pub mod compiler;
+pub mod conformance;
+pub mod dap;
+pub mod decode;
+pub mod decompile;
+pub mod disasm;
+mod fault;
+mod gas;
+pub mod gateway;
+mod heatmap;
+pub mod network;
+mod patch;
+pub mod program;
+mod record;
+mod registry;
+mod state;
+
+pub use arithmetic::{Arithmetic, Operator, Overflow};
+pub use batch::Batch;
+pub use cancel::Cancellation;
+pub use crash::{CrashReport, History, Trace};
+pub use fault::Fault;
+pub use gas::CostModel;
+pub use heatmap::Heatmap;
+pub use patch::{Edit, Patch, PatchError};
+pub use record::{Direction, Event, Recording, ReplayError};
+pub use registry::{Effect, InstructionSet, Operation, Role};
+
+#[derive(Clone, Debug)]
+pub enum Signal {
     HALT,
+    INPUT,
+    LIMIT,
+    CANCELLED,
+    TIMEOUT,
+    GAS,
 }
 
-#[derive(Debug)]
-enum OpCode {
+// how many instructions step() executes between checks of the cancellation
+// flag and the time budget
+pub const CHECK_INTERVAL: u64 = 1024;
+
+// how many executed instructions a computer remembers for crash reports
+pub const DEFAULT_HISTORY: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpCode {
     ADD, // 1
     MUL, // 2
     INPUT, // 3
@@ -18,89 +64,225 @@ enum OpCode {
     HALT, // 99
 }
 
-impl From<i32> for OpCode {
-    fn from(id: i32) -> Self {
+impl TryFrom<i32> for OpCode {
+    type Error = i32;
+
+    fn try_from(id: i32) -> Result<Self, Self::Error> {
         match id {
-            1 => Self::ADD,
-            2 => Self::MUL,
-            3 => Self::INPUT,
-            4 => Self::OUTPUT,
-            5 => Self::JIT,
-            6 => Self::JIF,
-            7 => Self::LT,
-            8 => Self::EQ,
-            99 => Self::HALT,
-            _ => panic!("unrecognized opcode id: {}", id),
+            1 => Ok(Self::ADD),
+            2 => Ok(Self::MUL),
+            3 => Ok(Self::INPUT),
+            4 => Ok(Self::OUTPUT),
+            5 => Ok(Self::JIT),
+            6 => Ok(Self::JIF),
+            7 => Ok(Self::LT),
+            8 => Ok(Self::EQ),
+            99 => Ok(Self::HALT),
+            _ => Err(id),
         }
     }
 }
 
 impl OpCode {
-    fn size(&self) -> usize {
+    pub const ALL: [Self; 9] = [
+        Self::ADD,
+        Self::MUL,
+        Self::INPUT,
+        Self::OUTPUT,
+        Self::JIT,
+        Self::JIF,
+        Self::LT,
+        Self::EQ,
+        Self::HALT,
+    ];
+
+    pub fn id(&self) -> i32 {
         match self {
-            Self::ADD => 4,
-            Self::MUL => 4,
-            Self::INPUT => 2,
-            Self::OUTPUT => 2,
-            Self::JIT => 3,
-            Self::JIF => 3,
-            Self::LT => 4,
-            Self::EQ => 4,
-            Self::HALT => 1,
+            Self::ADD => 1,
+            Self::MUL => 2,
+            Self::INPUT => 3,
+            Self::OUTPUT => 4,
+            Self::JIT => 5,
+            Self::JIF => 6,
+            Self::LT => 7,
+            Self::EQ => 8,
+            Self::HALT => 99,
         }
     }
 }
 
+impl Operation for OpCode {
+    fn mnemonic(&self) -> &str {
+        match self {
+            Self::ADD => "ADD",
+            Self::MUL => "MUL",
+            Self::INPUT => "INPUT",
+            Self::OUTPUT => "OUTPUT",
+            Self::JIT => "JIT",
+            Self::JIF => "JIF",
+            Self::LT => "LT",
+            Self::EQ => "EQ",
+            Self::HALT => "HALT",
+        }
+    }
+
+    fn roles(&self) -> &[Role] {
+        use Role::{READ, WRITE};
+
+        match self {
+            Self::ADD => &[READ, READ, WRITE],
+            Self::MUL => &[READ, READ, WRITE],
+            Self::INPUT => &[WRITE],
+            Self::OUTPUT => &[READ],
+            Self::JIT => &[READ, READ],
+            Self::JIF => &[READ, READ],
+            Self::LT => &[READ, READ, WRITE],
+            Self::EQ => &[READ, READ, WRITE],
+            Self::HALT => &[],
+        }
+    }
+
+    fn execute(
+        &self,
+        computer: &mut Computer,
+        params: &[usize]
+    ) -> Result<Effect, Fault> {
+        let effect = match self {
+            Self::ADD => {
+                let lhs = computer.read(params[0]);
+                let rhs = computer.read(params[1]);
+                let value = computer.arithmetic(Operator::ADD, lhs, rhs)?;
+                computer.write(params[2], value);
+                Effect::NEXT
+            },
+            Self::MUL => {
+                let lhs = computer.read(params[0]);
+                let rhs = computer.read(params[1]);
+                let value = computer.arithmetic(Operator::MUL, lhs, rhs)?;
+                computer.write(params[2], value);
+                Effect::NEXT
+            },
+            Self::INPUT => {
+                match computer.receive() {
+                    Some(value) => {
+                        computer.write(params[0], value);
+                        Effect::NEXT
+                    },
+                    None => Effect::BLOCK,
+                }
+            },
+            Self::OUTPUT => {
+                let value = computer.read(params[0]);
+                computer.send(value);
+                Effect::NEXT
+            },
+            Self::JIT => {
+                match computer.read(params[0]) {
+                    0 => Effect::NEXT,
+                    _ => Effect::JUMP(computer.read(params[1]) as usize),
+                }
+            },
+            Self::JIF => {
+                match computer.read(params[0]) {
+                    0 => Effect::JUMP(computer.read(params[1]) as usize),
+                    _ => Effect::NEXT,
+                }
+            },
+            Self::LT => {
+                let result = computer.read(params[0]) < computer.read(params[1]);
+
+                computer.write(params[2], match result {
+                    true => 1,
+                    false => 0,
+                });
+
+                Effect::NEXT
+            },
+            Self::EQ => {
+                let result = computer.read(params[0]) == computer.read(params[1]);
+
+                computer.write(params[2], match result {
+                    true => 1,
+                    false => 0,
+                });
+
+                Effect::NEXT
+            },
+            Self::HALT => Effect::HALT,
+        };
+
+        Ok(effect)
+    }
+}
+
 enum ParameterMode {
     POSITION,
     IMMEDIATE,
 }
 
-impl From<i32> for ParameterMode {
-    fn from(id: i32) -> Self {
+impl TryFrom<i32> for ParameterMode {
+    type Error = i32;
+
+    fn try_from(id: i32) -> Result<Self, Self::Error> {
         match id {
-            0 => Self::POSITION,
-            1 => Self::IMMEDIATE,
-            _ => panic!("unrecognized parameter mode id: {}", id),
+            0 => Ok(Self::POSITION),
+            1 => Ok(Self::IMMEDIATE),
+            _ => Err(id),
         }
     }
 }
 
 struct Instruction {
-    opcode: OpCode,
-    pmode1: ParameterMode,
-    pmode2: ParameterMode,
-    pmode3: ParameterMode,
+    opcode_id: i32,
+    pmode_ids: i32,
 }
 
 impl From<&Computer> for Instruction {
     fn from(computer: &Computer) -> Self {
         let address = computer.instruction_pointer;
-        let remainder = computer.memory[address];
-
-        fn f(x: i32, n: u32) -> (i32, i32) {
-            let d = 10i32.pow(n);
-            (x % d, x / d)
-        }
-
-        let (opcode_id, remainder) = f(remainder, 2);
-        let (pmode1_id, remainder) = f(remainder, 1);
-        let (pmode2_id, remainder) = f(remainder, 1);
-        let (pmode3_id, remainder) = f(remainder, 1);
+        Self::decode(computer.memory[address])
+    }
+}
 
+impl Instruction {
+    fn decode(value: i32) -> Self {
         Self {
-            opcode: opcode_id.into(),
-            pmode1: pmode1_id.into(),
-            pmode2: pmode2_id.into(),
-            pmode3: pmode3_id.into(),
+            opcode_id: value % 100,
+            pmode_ids: value / 100,
         }
     }
+
+    // parameter modes are read right-to-left, starting with the hundreds digit
+    fn pmode(&self, offset: usize) -> Result<ParameterMode, i32> {
+        let d = 10i32.pow(offset as u32 - 1);
+        ((self.pmode_ids / d) % 10).try_into()
+    }
 }
 
 #[derive(Clone)]
 pub struct Computer {
-    pub memory: Vec<i32>,
+    // only modified through write() or a patch, which keep memory_hash current
+    memory: Vec<i32>,
+    memory_hash: u64,
     instruction_pointer: usize,
+    instruction_set: Arc<InstructionSet>,
+    arithmetic: Arithmetic,
+    first_overflow: Option<Overflow>,
+    steps: u64,
+    step_limit: Option<u64>,
+    cancellation: Option<Cancellation>,
+    time_budget: Option<Duration>,
+    // when the time budget runs out, counted from the start of the last
+    // run(), or from the first step() when stepping without one
+    deadline: Option<Instant>,
+    // instructions executed since the last check for an interruption
+    unchecked: u64,
+    cost_model: CostModel,
+    gas_used: u64,
+    gas_limit: Option<u64>,
+    history: History,
+    recording: Option<Recording>,
+    heatmap: Option<Heatmap>,
     pub input_buffer: VecDeque<i32>,
     pub output_buffer: VecDeque<i32>,
 }
@@ -109,30 +291,288 @@ impl Computer {
     pub fn new(program: &[i32]) -> Self {
         Self {
             memory: program.to_vec(),
+            memory_hash: state::memory_hash(program),
             instruction_pointer: 0,
+            instruction_set: Arc::new(InstructionSet::default()),
+            arithmetic: Arithmetic::default(),
+            first_overflow: None,
+            steps: 0,
+            step_limit: None,
+            cancellation: None,
+            time_budget: None,
+            deadline: None,
+            unchecked: 0,
+            cost_model: CostModel::default(),
+            gas_used: 0,
+            gas_limit: None,
+            history: History::new(DEFAULT_HISTORY),
+            recording: None,
+            heatmap: None,
             input_buffer: VecDeque::new(),
             output_buffer: VecDeque::new(),
         }
     }
 
     pub fn with_inputs(&mut self, noun: i32, verb: i32) -> &mut Self {
-        self.memory[1] = noun;
-        self.memory[2] = verb;
+        let mut patch = Patch::new();
+        patch.set(1, noun).set(2, verb);
+        self.with_patch(&patch).unwrap()
+    }
+
+    // applies every edit in the patch, or none of them if any is out of range
+    // or does not find the values it expects
+    pub fn with_patch(&mut self, patch: &Patch) -> Result<&mut Self, PatchError> {
+        patch.apply(&mut self.memory)?;
+        Ok(self.rehash())
+    }
+
+    pub fn with_instruction_set(
+        &mut self,
+        instruction_set: InstructionSet
+    ) -> &mut Self {
+        self.instruction_set = Arc::new(instruction_set);
         self
     }
 
-    pub fn execute_program(&mut self) -> &Self {
-        loop {
-            let signal = self.execute_instruction();
+    pub fn instruction_set(&self) -> &InstructionSet {
+        &self.instruction_set
+    }
+
+    pub fn with_arithmetic(&mut self, arithmetic: Arithmetic) -> &mut Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    // the first ADD or MUL whose result did not fit in a word, whichever
+    // arithmetic policy was in effect at the time
+    pub fn first_overflow(&self) -> Option<&Overflow> {
+        self.first_overflow.as_ref()
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    // stops execution once the total number of executed instructions reaches
+    // the limit; the program can be resumed after raising or clearing it
+    pub fn with_step_limit(&mut self, step_limit: Option<u64>) -> &mut Self {
+        self.step_limit = step_limit;
+        self
+    }
+
+    // stops execution soon after the flag is cancelled
+    pub fn with_cancellation(
+        &mut self,
+        cancellation: Option<Cancellation>
+    ) -> &mut Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    // stops execution once it has been running for longer than the budget;
+    // each call to run() gets the full budget again
+    pub fn with_time_budget(&mut self, time_budget: Option<Duration>) -> &mut Self {
+        self.time_budget = time_budget;
+        self.deadline = None;
+        self.unchecked = 0;
+        self
+    }
+
+    pub fn with_cost_model(&mut self, cost_model: CostModel) -> &mut Self {
+        self.cost_model = cost_model;
+        self
+    }
+
+    // stops execution before any instruction whose cost would take the total
+    // gas used past the limit; like the step limit it can be raised to resume
+    pub fn with_gas_limit(&mut self, gas_limit: Option<u64>) -> &mut Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    // the total cost of the instructions executed so far
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    // the number of instructions executed so far
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    // keeps the last capacity executed instructions, discarding any history
+    // recorded so far; zero turns the history off
+    pub fn with_history(&mut self, capacity: usize) -> &mut Self {
+        self.history = History::new(capacity);
+        self
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn crash_report(&self, fault: &Fault) -> CrashReport {
+        CrashReport::new(self, fault)
+    }
 
-            match &signal {
-                Some(Signal::HALT) => {
-                    break;
-                },
-                None => (),
+    // starts recording every value consumed from the input buffer and pushed
+    // to the output buffer, discarding any previous recording
+    pub fn record(&mut self) -> &mut Self {
+        self.recording = Some(Recording::new());
+        self
+    }
+
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+
+    pub fn take_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    // starts counting reads, writes and executions per address, discarding
+    // any previous counts
+    pub fn instrument(&mut self) -> &mut Self {
+        self.heatmap = Some(Heatmap::new(self.memory.len()));
+        self
+    }
+
+    pub fn heatmap(&self) -> Option<&Heatmap> {
+        self.heatmap.as_ref()
+    }
+
+    pub fn take_heatmap(&mut self) -> Option<Heatmap> {
+        self.heatmap.take()
+    }
+
+    pub fn receive(&mut self) -> Option<i32> {
+        let value = self.input_buffer.pop_front()?;
+
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(self.steps, Direction::INPUT, value);
+        }
+
+        Some(value)
+    }
+
+    pub fn send(&mut self, value: i32) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(self.steps, Direction::OUTPUT, value);
+        }
+
+        self.output_buffer.push_back(value);
+    }
+
+    // inspects memory without counting as a read in the heatmap
+    pub fn memory(&self) -> &[i32] {
+        &self.memory
+    }
+
+    pub fn read(&mut self, address: usize) -> i32 {
+        if let Some(heatmap) = self.heatmap.as_mut() {
+            heatmap.read(address);
+        }
+
+        self.memory[address]
+    }
+
+    pub fn write(&mut self, address: usize, value: i32) {
+        if let Some(heatmap) = self.heatmap.as_mut() {
+            heatmap.write(address);
+        }
+
+        self.memory_hash ^= state::mix(address, self.memory[address]);
+        self.memory_hash ^= state::mix(address, value);
+        self.memory[address] = value;
+    }
+
+    // applies the arithmetic policy to the result of the instruction at the
+    // instruction pointer
+    pub fn arithmetic(
+        &mut self,
+        operator: Operator,
+        lhs: i32,
+        rhs: i32
+    ) -> Result<i32, Fault> {
+        let (result, overflowed) = self.arithmetic.apply(operator, lhs, rhs);
+
+        let overflow = Overflow {
+            address: self.instruction_pointer,
+            operator,
+            lhs,
+            rhs,
+        };
+
+        if overflowed && self.first_overflow.is_none() {
+            self.first_overflow = Some(overflow);
+        }
+
+        result.ok_or(Fault::OVERFLOW(overflow))
+    }
+
+    fn interrupted(&mut self) -> Option<Signal> {
+        if let Some(cancellation) = self.cancellation.as_ref() {
+            if cancellation.is_cancelled() {
+                return Some(Signal::CANCELLED);
+            }
+        }
+
+        if self.deadline.is_none() {
+            self.deadline = self.time_budget.map(|budget| Instant::now() + budget);
+        }
+
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Some(Signal::TIMEOUT),
+            _ => None,
+        }
+    }
+
+    // executes a single instruction unless the step limit, cancellation or
+    // time budget stops it first; anything which runs a program one
+    // instruction at a time should use this rather than execute_instruction()
+    pub fn step(&mut self) -> Result<Option<Signal>, Fault> {
+        if self.unchecked.is_multiple_of(CHECK_INTERVAL) {
+            if let Some(signal) = self.interrupted() {
+                return Ok(Some(signal));
             }
         }
 
+        if let Some(step_limit) = self.step_limit {
+            if self.steps >= step_limit {
+                return Ok(Some(Signal::LIMIT));
+            }
+        }
+
+        self.unchecked += 1;
+        self.execute_instruction()
+    }
+
+    // runs until the program halts, waits for input, reaches the step limit
+    // or is cancelled, returning the fault if one occurs; the computer is left
+    // at an instruction boundary so that it can be resumed with another run()
+    pub fn run(&mut self) -> Result<Signal, Fault> {
+        self.deadline = None;
+        self.unchecked = 0;
+
+        loop {
+            if let Some(signal) = self.step()? {
+                return Ok(signal);
+            }
+        }
+    }
+
+    // runs the program to completion, panicking if it faults or stops for
+    // any other reason before it halts
+    pub fn execute_program(&mut self) -> &Self {
+        match self.run() {
+            Ok(Signal::HALT) => (),
+            Ok(signal) => panic!(
+                "program stopped with {:?} at address {} before halting",
+                signal, self.instruction_pointer,
+            ),
+            Err(fault) => panic!("{}", self.crash_report(&fault)),
+        }
+
         self
     }
 
@@ -144,107 +584,86 @@ impl Computer {
         &self,
         offset: usize,
         pmode: ParameterMode
-    ) -> usize {
+    ) -> Result<usize, Fault> {
         let address = self.instruction_pointer;
 
-        match pmode {
-            ParameterMode::POSITION => self.memory[address + offset] as usize,
-            ParameterMode::IMMEDIATE => address + offset,
+        let target = match pmode {
+            ParameterMode::POSITION => self.memory[address + offset] as i64,
+            ParameterMode::IMMEDIATE => (address + offset) as i64,
+        };
+
+        match target >= 0 && (target as usize) < self.memory.len() {
+            true => Ok(target as usize),
+            false => Err(Fault::ADDRESS { address, target }),
         }
     }
 
-    fn execute_instruction(&mut self) -> Option<Signal> {
+    pub fn execute_instruction(&mut self) -> Result<Option<Signal>, Fault> {
         let ip = self.instruction_pointer;
 
-        let Instruction {
-            opcode,
-            pmode1,
-            pmode2,
-            pmode3
-        } = self.fetch_instruction();
-
-        let (ip, signal) = match opcode {
-            OpCode::ADD => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                let paddr2 = self.resolve_parameter_address(2, pmode2);
-                let paddr3 = self.resolve_parameter_address(3, pmode3);
-                self.memory[paddr3] = self.memory[paddr1] + self.memory[paddr2];
-                (ip + opcode.size(), None)
-            },
-            OpCode::MUL => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                let paddr2 = self.resolve_parameter_address(2, pmode2);
-                let paddr3 = self.resolve_parameter_address(3, pmode3);
-                self.memory[paddr3] = self.memory[paddr1] * self.memory[paddr2];
-                (ip + opcode.size(), None)
-            },
-            OpCode::INPUT => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                self.memory[paddr1] = self.input_buffer.pop_front().unwrap();
-                (ip + opcode.size(), None)
-            },
-            OpCode::OUTPUT => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                self.output_buffer.push_back(self.memory[paddr1]);
-                (ip + opcode.size(), None)
-            },
-            OpCode::JIT => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                let paddr2 = self.resolve_parameter_address(2, pmode2);
+        if ip >= self.memory.len() {
+            return Err(Fault::ADDRESS { address: ip, target: ip as i64 });
+        }
 
-                let ip = match self.memory[paddr1] {
-                    0 => ip + opcode.size(),
-                    _ => self.memory[paddr2] as usize,
-                };
+        let instruction = self.fetch_instruction();
 
-                (ip, None)
-            },
-            OpCode::JIF => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                let paddr2 = self.resolve_parameter_address(2, pmode2);
-
-                let ip = match self.memory[paddr1] {
-                    0 => self.memory[paddr2] as usize,
-                    _ => ip + opcode.size(),
-                };
+        let operation = match self.instruction_set.get(instruction.opcode_id) {
+            Some(operation) => Arc::clone(operation),
+            None => return Err(Fault::OPCODE {
+                address: ip,
+                id: instruction.opcode_id,
+            }),
+        };
 
-                (ip, None)
-            },
-            OpCode::LT => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                let paddr2 = self.resolve_parameter_address(2, pmode2);
-                let paddr3 = self.resolve_parameter_address(3, pmode3);
+        let cost = self.cost_model.cost(instruction.opcode_id);
 
-                let result = self.memory[paddr1] < self.memory[paddr2];
+        // a total which no longer fits is past any limit there could be
+        let Some(gas_used) = self.gas_used.checked_add(cost) else {
+            return Ok(Some(Signal::GAS));
+        };
 
-                self.memory[paddr3] = match result {
-                    true => 1,
-                    false => 0,
-                };
+        if let Some(gas_limit) = self.gas_limit {
+            if gas_used > gas_limit {
+                return Ok(Some(Signal::GAS));
+            }
+        }
 
-                (ip + opcode.size(), None)
-            },
-            OpCode::EQ => {
-                let paddr1 = self.resolve_parameter_address(1, pmode1);
-                let paddr2 = self.resolve_parameter_address(2, pmode2);
-                let paddr3 = self.resolve_parameter_address(3, pmode3);
+        if ip + operation.arity() >= self.memory.len() {
+            return Err(Fault::ADDRESS {
+                address: ip,
+                target: (ip + operation.arity()) as i64,
+            });
+        }
 
-                let result = self.memory[paddr1] == self.memory[paddr2];
+        let params = (1..=operation.arity())
+            .map(|offset| {
+                let pmode = instruction.pmode(offset)
+                    .map_err(|id| Fault::PMODE { address: ip, id })?;
+                self.resolve_parameter_address(offset, pmode)
+            })
+            .collect::<Result<Vec<usize>, Fault>>()?;
 
-                self.memory[paddr3] = match result {
-                    true => 1,
-                    false => 0,
-                };
+        self.history.capture(&self.memory[ip..ip + operation.size()]);
 
-                (ip + opcode.size(), None)
-            },
-            OpCode::HALT => {
-                (ip + opcode.size(), Some(Signal::HALT))
+        let (next, signal) = match operation.execute(self, &params)? {
+            Effect::NEXT => (ip + operation.size(), None),
+            Effect::JUMP(address) if address >= self.memory.len() => {
+                return Err(Fault::ADDRESS { address: ip, target: address as i64 });
             },
+            Effect::JUMP(address) => (address, None),
+            Effect::HALT => (ip + operation.size(), Some(Signal::HALT)),
+            Effect::BLOCK => return Ok(Some(Signal::INPUT)),
         };
 
-        self.instruction_pointer = ip;
+        if let Some(heatmap) = self.heatmap.as_mut() {
+            heatmap.execute(ip);
+        }
+
+        self.history.push(self.steps, ip);
+        self.instruction_pointer = next;
+        self.steps += 1;
+        self.gas_used = gas_used;
 
-        signal
+        Ok(signal)
     }
 }
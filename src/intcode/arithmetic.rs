@@ -0,0 +1,95 @@
+use std::fmt;
+use std::str::FromStr;
+
+// what ADD and MUL should do when the result does not fit in a word
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+    WRAPPING,
+    #[default]
+    CHECKED,
+    SATURATING,
+}
+
+impl FromStr for Arithmetic {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "wrapping" => Ok(Self::WRAPPING),
+            "checked" => Ok(Self::CHECKED),
+            "saturating" => Ok(Self::SATURATING),
+            _ => Err(format!("unknown arithmetic policy: {}", name)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    ADD,
+    MUL,
+}
+
+impl Operator {
+    fn overflowing(&self, lhs: i32, rhs: i32) -> (i32, bool) {
+        match self {
+            Self::ADD => lhs.overflowing_add(rhs),
+            Self::MUL => lhs.overflowing_mul(rhs),
+        }
+    }
+
+    fn saturating(&self, lhs: i32, rhs: i32) -> i32 {
+        match self {
+            Self::ADD => lhs.saturating_add(rhs),
+            Self::MUL => lhs.saturating_mul(rhs),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ADD => write!(f, "+"),
+            Self::MUL => write!(f, "*"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    pub address: usize,
+    pub operator: Operator,
+    pub lhs: i32,
+    pub rhs: i32,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "arithmetic overflow at address {}: {} {} {}",
+            self.address, self.lhs, self.operator, self.rhs,
+        )
+    }
+}
+
+impl Arithmetic {
+    // returns the result under this policy along with the overflow, if any;
+    // a checked overflow produces no result
+    pub fn apply(
+        &self,
+        operator: Operator,
+        lhs: i32,
+        rhs: i32
+    ) -> (Option<i32>, bool) {
+        let (wrapped, overflowed) = operator.overflowing(lhs, rhs);
+
+        let result = match (self, overflowed) {
+            (_, false) => Some(wrapped),
+            (Self::WRAPPING, true) => Some(wrapped),
+            (Self::SATURATING, true) => Some(operator.saturating(lhs, rhs)),
+            (Self::CHECKED, true) => None,
+        };
+
+        (result, overflowed)
+    }
+}
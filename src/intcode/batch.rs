@@ -0,0 +1,135 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::Computer;
+
+// runs a job against a fresh clone of a base computer for every setup,
+// spreading the setups over worker threads
+//
+//     let answer = Batch::new(&computer).find(
+//         pairs,
+//         |computer, &(noun, verb)| computer.with_inputs(noun, verb)...,
+//         |&result| result == 19690720,
+//     );
+pub struct Batch {
+    base: Computer,
+    threads: usize,
+}
+
+// a finished job along with the position of its setup in the input
+struct Outcome<T, R> {
+    index: usize,
+    setup: T,
+    result: R,
+    matched: bool,
+}
+
+impl Batch {
+    pub fn new(base: &Computer) -> Self {
+        let threads = thread::available_parallelism()
+            .map(|threads| threads.get())
+            .unwrap_or(1);
+
+        Self { base: base.clone(), threads }
+    }
+
+    pub fn with_threads(&mut self, threads: usize) -> &mut Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    // setups are pulled lazily by the workers; once a result matches, no
+    // setup after it is started, but every setup before it still runs so that
+    // the first match in input order wins
+    fn dispatch<T, R, I, F, P>(
+        &self,
+        setups: I,
+        job: F,
+        predicate: P,
+        stop_on_match: bool
+    ) -> Vec<Outcome<T, R>>
+    where
+        T: Send,
+        R: Send,
+        I: Iterator<Item = T> + Send,
+        F: Fn(&mut Computer, &T) -> R + Sync,
+        P: Fn(&R) -> bool + Sync,
+    {
+        let setups = Mutex::new(setups.enumerate());
+        let first_match = AtomicUsize::new(usize::MAX);
+
+        let worker = || {
+            let mut outcomes = Vec::new();
+
+            loop {
+                let next = setups.lock().unwrap().next();
+
+                let Some((index, setup)) = next else {
+                    break;
+                };
+
+                if index > first_match.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let mut computer = self.base.clone();
+                let result = job(&mut computer, &setup);
+                let matched = predicate(&result);
+
+                if matched && stop_on_match {
+                    first_match.fetch_min(index, Ordering::Relaxed);
+                }
+
+                outcomes.push(Outcome { index, setup, result, matched });
+            }
+
+            outcomes
+        };
+
+        let mut outcomes = thread::scope(|scope| {
+            let workers = (0..self.threads)
+                .map(|_| scope.spawn(worker))
+                .collect::<Vec<_>>();
+
+            workers.into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect::<Vec<Outcome<T, R>>>()
+        });
+
+        outcomes.sort_by_key(|outcome| outcome.index);
+        outcomes
+    }
+
+    // the result of every setup, in the order of the setups
+    pub fn map<T, R, I, F>(&self, setups: I, job: F) -> Vec<R>
+    where
+        T: Send,
+        R: Send,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send,
+        F: Fn(&mut Computer, &T) -> R + Sync,
+    {
+        self.dispatch(setups.into_iter(), job, |_| false, false)
+            .into_iter()
+            .map(|outcome| outcome.result)
+            .collect()
+    }
+
+    // the first setup in order whose result matches the predicate, stopping
+    // early instead of trying every setup
+    pub fn find<T, R, I, F, P>(&self, setups: I, job: F, predicate: P) -> Option<(T, R)>
+    where
+        T: Send,
+        R: Send,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send,
+        F: Fn(&mut Computer, &T) -> R + Sync,
+        P: Fn(&R) -> bool + Sync,
+    {
+        self.dispatch(setups.into_iter(), job, predicate, true)
+            .into_iter()
+            .find(|outcome| outcome.matched)
+            .map(|outcome| (outcome.setup, outcome.result))
+    }
+}
@@ -0,0 +1,828 @@
+// a compiler for a tiny imperative language which targets the built-in
+// Intcode instruction set
+//
+//     var n = input();
+//     var i = 0;
+//     while (i < n) {
+//         output(i * i);
+//         i = i + 1;
+//     }
+//
+// every value is a word; comparisons and logical operators produce 0 or 1,
+// and && and || always evaluate both sides
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{OpCode, Operation};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompileError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    NUMBER(i32),
+    IDENT(String),
+    SYMBOL(&'static str),
+    END,
+}
+
+#[derive(Clone, Debug)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+// longer symbols come first so that '<=' is not read as '<' then '='
+const SYMBOLS: [&str; 19] = [
+    "==", "!=", "<=", ">=", "&&", "||",
+    "<", ">", "+", "-", "*", "!", "=", "(", ")", "{", "}", ";", ",",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Spanned>, CompileError> {
+    let chars = source.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    let (mut line, mut column) = (1, 1);
+
+    while position < chars.len() {
+        let c = chars[position];
+
+        if c == '\n' {
+            position += 1;
+            line += 1;
+            column = 1;
+            continue;
+        }
+
+        if c.is_whitespace() {
+            position += 1;
+            column += 1;
+            continue;
+        }
+
+        if c == '/' && chars.get(position + 1) == Some(&'/') {
+            while position < chars.len() && chars[position] != '\n' {
+                position += 1;
+            }
+            continue;
+        }
+
+        let start = position;
+
+        let error = |message: String| {
+            Err(CompileError { line, column, message })
+        };
+
+        let token = if c.is_ascii_digit() {
+            while position < chars.len() && chars[position].is_ascii_digit() {
+                position += 1;
+            }
+
+            let text = chars[start..position].iter().collect::<String>();
+
+            match text.parse::<i32>() {
+                Ok(value) => Token::NUMBER(value),
+                Err(_) => return error(format!("number too large: {}", text)),
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while position < chars.len()
+                && (chars[position].is_ascii_alphanumeric() || chars[position] == '_')
+            {
+                position += 1;
+            }
+
+            Token::IDENT(chars[start..position].iter().collect())
+        } else {
+            let symbol = SYMBOLS.iter().find(|symbol| {
+                symbol.chars()
+                    .enumerate()
+                    .all(|(offset, c)| chars.get(position + offset) == Some(&c))
+            });
+
+            match symbol {
+                Some(symbol) => {
+                    position += symbol.len();
+                    Token::SYMBOL(symbol)
+                },
+                None => return error(format!("unexpected character {:?}", c)),
+            }
+        };
+
+        tokens.push(Spanned { token, line, column });
+        column += position - start;
+    }
+
+    tokens.push(Spanned { token: Token::END, line, column });
+
+    Ok(tokens)
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    NUMBER(i32),
+    VARIABLE(String, usize, usize),
+    INPUT,
+    UNARY(&'static str, Box<Expr>),
+    BINARY(&'static str, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug)]
+enum Stmt {
+    DECLARE(String, Expr, usize, usize),
+    ASSIGN(String, Expr, usize, usize),
+    OUTPUT(Expr),
+    IF(Expr, Vec<Stmt>, Vec<Stmt>),
+    WHILE(Expr, Vec<Stmt>),
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Spanned {
+        &self.tokens[self.position]
+    }
+
+    fn advance(&mut self) -> Spanned {
+        let token = self.tokens[self.position].clone();
+
+        if token.token != Token::END {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, CompileError> {
+        let Spanned { line, column, .. } = *self.peek();
+        Err(CompileError { line, column, message })
+    }
+
+    fn describe(token: &Token) -> String {
+        match token {
+            Token::NUMBER(value) => format!("'{}'", value),
+            Token::IDENT(name) => format!("'{}'", name),
+            Token::SYMBOL(symbol) => format!("'{}'", symbol),
+            Token::END => "end of input".to_string(),
+        }
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(&self.peek().token, Token::SYMBOL(s) if *s == symbol)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().token, Token::IDENT(name) if name == keyword)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), CompileError> {
+        if !self.is_symbol(symbol) {
+            let found = Self::describe(&self.peek().token);
+            return self.error(format!("expected '{}', found {}", symbol, found));
+        }
+
+        self.advance();
+        Ok(())
+    }
+
+    fn expect_ident(&mut self) -> Result<(String, usize, usize), CompileError> {
+        match self.peek().token.clone() {
+            Token::IDENT(name) if !is_keyword(&name) => {
+                let Spanned { line, column, .. } = self.advance();
+                Ok((name, line, column))
+            },
+            token => {
+                let found = Self::describe(&token);
+                self.error(format!("expected a variable name, found {}", found))
+            },
+        }
+    }
+
+    fn program(&mut self) -> Result<Vec<Stmt>, CompileError> {
+        let mut statements = Vec::new();
+
+        while self.peek().token != Token::END {
+            statements.push(self.statement()?);
+        }
+
+        Ok(statements)
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, CompileError> {
+        self.expect_symbol("{")?;
+
+        let mut statements = Vec::new();
+
+        while !self.is_symbol("}") {
+            if self.peek().token == Token::END {
+                return self.error("expected '}', found end of input".to_string());
+            }
+
+            statements.push(self.statement()?);
+        }
+
+        self.expect_symbol("}")?;
+
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, CompileError> {
+        if self.is_keyword("var") {
+            self.advance();
+            let (name, line, column) = self.expect_ident()?;
+            self.expect_symbol("=")?;
+            let value = self.expression()?;
+            self.expect_symbol(";")?;
+            return Ok(Stmt::DECLARE(name, value, line, column));
+        }
+
+        if self.is_keyword("output") {
+            self.advance();
+            self.expect_symbol("(")?;
+            let value = self.expression()?;
+            self.expect_symbol(")")?;
+            self.expect_symbol(";")?;
+            return Ok(Stmt::OUTPUT(value));
+        }
+
+        if self.is_keyword("if") {
+            self.advance();
+            self.expect_symbol("(")?;
+            let condition = self.expression()?;
+            self.expect_symbol(")")?;
+            let then = self.block()?;
+
+            let otherwise = match self.is_keyword("else") {
+                true => {
+                    self.advance();
+                    match self.is_keyword("if") {
+                        true => vec![self.statement()?],
+                        false => self.block()?,
+                    }
+                },
+                false => Vec::new(),
+            };
+
+            return Ok(Stmt::IF(condition, then, otherwise));
+        }
+
+        if self.is_keyword("while") {
+            self.advance();
+            self.expect_symbol("(")?;
+            let condition = self.expression()?;
+            self.expect_symbol(")")?;
+            let body = self.block()?;
+            return Ok(Stmt::WHILE(condition, body));
+        }
+
+        let (name, line, column) = self.expect_ident()?;
+        self.expect_symbol("=")?;
+        let value = self.expression()?;
+        self.expect_symbol(";")?;
+
+        Ok(Stmt::ASSIGN(name, value, line, column))
+    }
+
+    fn expression(&mut self) -> Result<Expr, CompileError> {
+        self.binary(0)
+    }
+
+    // operators by precedence, loosest first
+    const LEVELS: [&'static [&'static str]; 5] = [
+        &["||"],
+        &["&&"],
+        &["==", "!="],
+        &["<", "<=", ">", ">="],
+        &["+", "-"],
+    ];
+
+    fn binary(&mut self, level: usize) -> Result<Expr, CompileError> {
+        if level == Self::LEVELS.len() {
+            return self.product();
+        }
+
+        let mut lhs = self.binary(level + 1)?;
+
+        while let Token::SYMBOL(symbol) = self.peek().token {
+            if !Self::LEVELS[level].contains(&symbol) {
+                break;
+            }
+
+            self.advance();
+            let rhs = self.binary(level + 1)?;
+            lhs = Expr::BINARY(symbol, Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn product(&mut self) -> Result<Expr, CompileError> {
+        let mut lhs = self.unary()?;
+
+        while self.is_symbol("*") {
+            self.advance();
+            let rhs = self.unary()?;
+            lhs = Expr::BINARY("*", Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CompileError> {
+        for symbol in ["-", "!"] {
+            if self.is_symbol(symbol) {
+                self.advance();
+                let operand = self.unary()?;
+                return Ok(Expr::UNARY(symbol, Box::new(operand)));
+            }
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, CompileError> {
+        match self.peek().token.clone() {
+            Token::NUMBER(value) => {
+                self.advance();
+                Ok(Expr::NUMBER(value))
+            },
+            Token::SYMBOL("(") => {
+                self.advance();
+                let expr = self.expression()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            },
+            Token::IDENT(name) if name == "input" => {
+                self.advance();
+                self.expect_symbol("(")?;
+                self.expect_symbol(")")?;
+                Ok(Expr::INPUT)
+            },
+            Token::IDENT(name) if !is_keyword(&name) => {
+                let Spanned { line, column, .. } = self.advance();
+                Ok(Expr::VARIABLE(name, line, column))
+            },
+            token => {
+                let found = Self::describe(&token);
+                self.error(format!("expected an expression, found {}", found))
+            },
+        }
+    }
+}
+
+fn is_keyword(name: &str) -> bool {
+    matches!(name, "var" | "if" | "else" | "while" | "input" | "output")
+}
+
+// an operand before the final memory layout is known
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operand {
+    IMM(i32),
+    VAR(usize),
+    TEMP(usize),
+    LABEL(usize),
+}
+
+struct Generator {
+    // each instruction is emitted as an opcode and its unresolved operands
+    code: Vec<(OpCode, Vec<Operand>)>,
+    variables: HashMap<String, usize>,
+    temps: usize,
+    max_temps: usize,
+    labels: Vec<Option<usize>>,
+}
+
+impl Generator {
+    fn emit(&mut self, opcode: OpCode, operands: Vec<Operand>) {
+        debug_assert_eq!(opcode.arity(), operands.len());
+        self.code.push((opcode, operands));
+    }
+
+    fn label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.code.len());
+    }
+
+    fn temp(&mut self) -> Operand {
+        self.temps += 1;
+        self.max_temps = self.max_temps.max(self.temps);
+        Operand::TEMP(self.temps - 1)
+    }
+
+    fn variable(&self, name: &str, line: usize, column: usize) -> Result<Operand, CompileError> {
+        match self.variables.get(name) {
+            Some(&index) => Ok(Operand::VAR(index)),
+            None => Err(CompileError {
+                line,
+                column,
+                message: format!("undeclared variable '{}'", name),
+            }),
+        }
+    }
+
+    fn statements(&mut self, statements: &[Stmt]) -> Result<(), CompileError> {
+        for statement in statements {
+            self.statement(statement)?;
+        }
+
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &Stmt) -> Result<(), CompileError> {
+        // temporaries only live for the duration of a statement
+        self.temps = 0;
+
+        match statement {
+            Stmt::DECLARE(name, value, line, column) => {
+                if self.variables.contains_key(name) {
+                    return Err(CompileError {
+                        line: *line,
+                        column: *column,
+                        message: format!("variable '{}' is already declared", name),
+                    });
+                }
+
+                // the new variable is not in scope in its own initializer
+                let value = self.expression(value)?;
+                let index = self.variables.len();
+                self.variables.insert(name.clone(), index);
+                self.assign(Operand::VAR(index), value);
+            },
+            Stmt::ASSIGN(name, value, line, column) => {
+                let variable = self.variable(name, *line, *column)?;
+                let value = self.expression(value)?;
+                self.assign(variable, value);
+            },
+            Stmt::OUTPUT(value) => {
+                let value = self.expression(value)?;
+                self.emit(OpCode::OUTPUT, vec![value]);
+            },
+            Stmt::IF(condition, then, otherwise) => {
+                let else_label = self.label();
+                let end_label = self.label();
+
+                let condition = self.expression(condition)?;
+                self.emit(OpCode::JIF, vec![condition, Operand::LABEL(else_label)]);
+                self.statements(then)?;
+
+                if otherwise.is_empty() {
+                    self.place(else_label);
+                } else {
+                    self.emit(OpCode::JIT, vec![Operand::IMM(1), Operand::LABEL(end_label)]);
+                    self.place(else_label);
+                    self.statements(otherwise)?;
+                }
+
+                self.place(end_label);
+            },
+            Stmt::WHILE(condition, body) => {
+                let start_label = self.label();
+                let end_label = self.label();
+
+                self.place(start_label);
+                let condition = self.expression(condition)?;
+                self.emit(OpCode::JIF, vec![condition, Operand::LABEL(end_label)]);
+                self.statements(body)?;
+                self.emit(OpCode::JIT, vec![Operand::IMM(1), Operand::LABEL(start_label)]);
+                self.place(end_label);
+            },
+        }
+
+        Ok(())
+    }
+
+    fn assign(&mut self, destination: Operand, value: Operand) {
+        if destination != value {
+            self.emit(OpCode::ADD, vec![value, Operand::IMM(0), destination]);
+        }
+    }
+
+    fn expression(&mut self, expr: &Expr) -> Result<Operand, CompileError> {
+        let operand = match expr {
+            Expr::NUMBER(value) => Operand::IMM(*value),
+            Expr::VARIABLE(name, line, column) => self.variable(name, *line, *column)?,
+            Expr::INPUT => {
+                let result = self.temp();
+                self.emit(OpCode::INPUT, vec![result]);
+                result
+            },
+            Expr::UNARY(operator, operand) => {
+                let operand = self.expression(operand)?;
+                let result = self.temp();
+
+                match *operator {
+                    "-" => self.emit(OpCode::MUL, vec![operand, Operand::IMM(-1), result]),
+                    _ => self.emit(OpCode::EQ, vec![operand, Operand::IMM(0), result]),
+                }
+
+                result
+            },
+            Expr::BINARY(operator, lhs, rhs) => {
+                let lhs = self.expression(lhs)?;
+                let rhs = self.expression(rhs)?;
+                let result = self.temp();
+                self.binary(operator, lhs, rhs, result);
+                result
+            },
+        };
+
+        Ok(operand)
+    }
+
+    fn binary(&mut self, operator: &str, lhs: Operand, rhs: Operand, result: Operand) {
+        use OpCode::{ADD, EQ, LT, MUL};
+
+        match operator {
+            "+" => self.emit(ADD, vec![lhs, rhs, result]),
+            "*" => self.emit(MUL, vec![lhs, rhs, result]),
+            "-" => {
+                self.emit(MUL, vec![rhs, Operand::IMM(-1), result]);
+                self.emit(ADD, vec![lhs, result, result]);
+            },
+            "<" => self.emit(LT, vec![lhs, rhs, result]),
+            ">" => self.emit(LT, vec![rhs, lhs, result]),
+            "==" => self.emit(EQ, vec![lhs, rhs, result]),
+            "!=" | "<=" | ">=" => {
+                match operator {
+                    "!=" => self.emit(EQ, vec![lhs, rhs, result]),
+                    "<=" => self.emit(LT, vec![rhs, lhs, result]),
+                    _ => self.emit(LT, vec![lhs, rhs, result]),
+                }
+                self.emit(EQ, vec![result, Operand::IMM(0), result]);
+            },
+            "&&" => {
+                // both sides are normalised to 0 or 1 before multiplying
+                let other = self.temp();
+                self.emit(EQ, vec![lhs, Operand::IMM(0), result]);
+                self.emit(EQ, vec![rhs, Operand::IMM(0), other]);
+                self.emit(ADD, vec![result, other, result]);
+                self.emit(EQ, vec![result, Operand::IMM(0), result]);
+            },
+            "||" => {
+                let other = self.temp();
+                self.emit(EQ, vec![lhs, Operand::IMM(0), result]);
+                self.emit(EQ, vec![rhs, Operand::IMM(0), other]);
+                self.emit(MUL, vec![result, other, result]);
+                self.emit(EQ, vec![result, Operand::IMM(0), result]);
+            },
+            _ => unreachable!("unknown operator {}", operator),
+        }
+    }
+
+    // lays out the code followed by variables then temporaries, resolving
+    // every operand to its final address
+    fn assemble(self) -> Vec<i32> {
+        let offsets = self.code.iter()
+            .scan(0, |address, (opcode, _)| {
+                let offset = *address;
+                *address += opcode.size();
+                Some(offset)
+            })
+            .collect::<Vec<usize>>();
+
+        let code_size = self.code.iter()
+            .map(|(opcode, _)| opcode.size())
+            .sum::<usize>()
+            + OpCode::HALT.size();
+
+        let variables = code_size;
+        let temps = variables + self.variables.len();
+
+        let address_of = |instruction: usize| {
+            offsets.get(instruction).copied().unwrap_or(code_size - 1)
+        };
+
+        let mut memory = Vec::new();
+
+        for (opcode, operands) in self.code.iter() {
+            let mut word = opcode.id();
+            let mut params = Vec::new();
+
+            for (index, operand) in operands.iter().enumerate() {
+                let (immediate, value) = match *operand {
+                    Operand::IMM(value) => (true, value),
+                    Operand::LABEL(label) => {
+                        let instruction = self.labels[label].unwrap();
+                        (true, address_of(instruction) as i32)
+                    },
+                    Operand::VAR(index) => (false, (variables + index) as i32),
+                    Operand::TEMP(index) => (false, (temps + index) as i32),
+                };
+
+                if immediate {
+                    word += 10i32.pow(index as u32 + 2);
+                }
+
+                params.push(value);
+            }
+
+            memory.push(word);
+            memory.extend(params);
+        }
+
+        memory.push(OpCode::HALT.id());
+        memory.resize(temps + self.max_temps, 0);
+
+        memory
+    }
+}
+
+pub fn compile(source: &str) -> Result<Vec<i32>, CompileError> {
+    let tokens = tokenize(source)?;
+    let statements = Parser { tokens, position: 0 }.program()?;
+
+    let mut generator = Generator {
+        code: Vec::new(),
+        variables: HashMap::new(),
+        temps: 0,
+        max_temps: 0,
+        labels: Vec::new(),
+    };
+
+    generator.statements(&statements)?;
+
+    Ok(generator.assemble())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{Computer, Signal};
+
+    // compiles the source, runs it to completion on the given inputs and
+    // returns everything it output
+    fn run(source: &str, inputs: &[i32]) -> Vec<i32> {
+        let program = compile(source).unwrap();
+        let mut computer = Computer::new(&program);
+
+        computer.input_buffer.extend(inputs.iter());
+
+        assert!(matches!(computer.run(), Ok(Signal::HALT)));
+
+        computer.output_buffer.drain(..).collect()
+    }
+
+    fn error(source: &str) -> (usize, usize, String) {
+        let error = compile(source).unwrap_err();
+        (error.line, error.column, error.message)
+    }
+
+    #[test]
+    fn arithmetic() {
+        let source = "
+            output(2 + 3 * 4);
+            output((2 + 3) * 4);
+            output(10 - 3 - 2);
+            output(-5);
+            output(-(2 - 7));
+            output(3 - -4);
+            output(-2 * -3);
+        ";
+
+        assert_eq!(run(source, &[]), [14, 20, 5, -5, 5, 7, 6]);
+    }
+
+    #[test]
+    fn comparisons() {
+        let source = "
+            var a = input();
+            var b = input();
+            output(a == b);
+            output(a != b);
+            output(a < b);
+            output(a <= b);
+            output(a > b);
+            output(a >= b);
+        ";
+
+        assert_eq!(run(source, &[1, 2]), [0, 1, 1, 1, 0, 0]);
+        assert_eq!(run(source, &[2, 2]), [1, 0, 0, 1, 0, 1]);
+        assert_eq!(run(source, &[3, 2]), [0, 1, 0, 0, 1, 1]);
+        assert_eq!(run(source, &[-3, 2]), [0, 1, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn logical_operators() {
+        let source = "
+            var a = input();
+            var b = input();
+            output(a && b);
+            output(a || b);
+            output(!a);
+            output(a < 5 && b > 5 || a == b);
+        ";
+
+        assert_eq!(run(source, &[0, 0]), [0, 0, 1, 1]);
+        assert_eq!(run(source, &[0, 7]), [0, 1, 1, 1]);
+        assert_eq!(run(source, &[7, 0]), [0, 1, 0, 0]);
+        assert_eq!(run(source, &[7, 7]), [1, 1, 0, 1]);
+    }
+
+    #[test]
+    fn if_else_chain() {
+        let source = "
+            var n = input();
+            if (n < 0) {
+                output(-1);
+            } else if (n == 0) {
+                output(0);
+            } else if (n < 10) {
+                output(1);
+            } else {
+                output(2);
+            }
+            output(99);
+        ";
+
+        assert_eq!(run(source, &[-4]), [-1, 99]);
+        assert_eq!(run(source, &[0]), [0, 99]);
+        assert_eq!(run(source, &[5]), [1, 99]);
+        assert_eq!(run(source, &[50]), [2, 99]);
+    }
+
+    #[test]
+    fn while_loop() {
+        let source = "
+            var n = input();
+            var i = 1;
+            var total = 0;
+            while (i <= n) {
+                total = total + i;
+                i = i + 1;
+            }
+            output(total);
+        ";
+
+        assert_eq!(run(source, &[0]), [0]);
+        assert_eq!(run(source, &[10]), [55]);
+    }
+
+    #[test]
+    fn nested_loops() {
+        let source = "
+            var i = 0;
+            while (i < 3) {
+                var j = 0;
+                while (j < i) {
+                    output(i * 10 + j);
+                    j = j + 1;
+                }
+                i = i + 1;
+            }
+        ";
+
+        assert_eq!(run(source, &[]), [10, 20, 21]);
+    }
+
+    #[test]
+    fn input_is_read_in_order() {
+        let source = "
+            var a = input();
+            output(input() - a);
+            output(a);
+        ";
+
+        assert_eq!(run(source, &[3, 10]), [7, 3]);
+    }
+
+    #[test]
+    fn undeclared_variable() {
+        let (line, column, message) = error("var a = 1;\noutput(a + b);");
+        assert_eq!((line, column), (2, 12));
+        assert_eq!(message, "undeclared variable 'b'");
+    }
+
+    #[test]
+    fn redeclared_variable() {
+        let (line, column, message) = error("var a = 1;\n  var a = 2;");
+        assert_eq!((line, column), (2, 7));
+        assert_eq!(message, "variable 'a' is already declared");
+    }
+
+    #[test]
+    fn oversized_literal() {
+        let (line, column, message) = error("output(1);\noutput(99999999999);");
+        assert_eq!((line, column), (2, 8));
+        assert_eq!(message, "number too large: 99999999999");
+    }
+}
@@ -0,0 +1,294 @@
+// runs the case files under conformance/ against an Intcode engine
+//
+// each case is a file of 'key: value' lines, with '#' starting a comment:
+//
+//     program: 3,9,8,9,10,9,4,9,99,-1,8
+//     input: 8
+//     output: 1
+//     memory: 3,9,8,9,10,9,4,9,99,1,8    # optional final memory
+//     status: halt                       # halt, input, limit or fault
+//     steps: 1000                        # optional step limit
+//
+// input, output and memory are comma-separated and may be left empty
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{program, Computer, Signal};
+
+// the step limit for cases which do not set one, so that a broken engine
+// cannot hang the suite
+pub const DEFAULT_STEPS: u64 = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    HALT,
+    INPUT,
+    LIMIT,
+    FAULT,
+    // the engine stopped for some other reason, such as running out of time;
+    // no case can expect it, so it always fails
+    INTERRUPTED,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::HALT => "halt",
+            Self::INPUT => "input",
+            Self::LIMIT => "limit",
+            Self::FAULT => "fault",
+            Self::INTERRUPTED => "interrupted",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "halt" => Ok(Self::HALT),
+            "input" => Ok(Self::INPUT),
+            "limit" => Ok(Self::LIMIT),
+            "fault" => Ok(Self::FAULT),
+            _ => Err(format!("invalid status: {}", text)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Case {
+    pub name: String,
+    pub program: Vec<i32>,
+    pub input: Vec<i32>,
+    pub output: Vec<i32>,
+    pub memory: Option<Vec<i32>>,
+    pub status: Status,
+    pub steps: u64,
+}
+
+fn parse_words(text: &str) -> Result<Vec<i32>, String> {
+    match text.trim().is_empty() {
+        true => Ok(Vec::new()),
+        false => program::parse(text),
+    }
+}
+
+impl Case {
+    pub fn parse(name: &str, text: &str) -> Result<Self, String> {
+        let mut program = None;
+        let mut case = Self {
+            name: name.to_string(),
+            program: Vec::new(),
+            input: Vec::new(),
+            output: Vec::new(),
+            memory: None,
+            status: Status::HALT,
+            steps: DEFAULT_STEPS,
+        };
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |message: String| format!("line {}: {}", index + 1, message);
+
+            let (key, value) = line.split_once(':')
+                .ok_or_else(|| error(format!("expected 'key: value': {}", line)))?;
+
+            match key.trim() {
+                "program" => program = Some(parse_words(value).map_err(error)?),
+                "input" => case.input = parse_words(value).map_err(error)?,
+                "output" => case.output = parse_words(value).map_err(error)?,
+                "memory" => case.memory = Some(parse_words(value).map_err(error)?),
+                "status" => case.status = value.trim().parse().map_err(error)?,
+                "steps" => {
+                    case.steps = value.trim().parse()
+                        .map_err(|_| error(format!("invalid step limit: {}", value)))?;
+                },
+                key => return Err(error(format!("unknown key: {}", key))),
+            }
+        }
+
+        case.program = program.ok_or("missing program")?;
+
+        Ok(case)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+
+        let name = path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let text = fs::read_to_string(path)?;
+
+        Self::parse(&name, &text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+// every '.case' file in the directory, sorted by name
+pub fn load_suite<P: AsRef<Path>>(directory: P) -> io::Result<Vec<Case>> {
+    let mut paths = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "case"));
+    paths.sort();
+
+    paths.iter()
+        .map(|path| {
+            Case::load(path).map_err(|e| {
+                io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+            })
+        })
+        .collect()
+}
+
+// what an engine reports after running a case
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub status: Status,
+    pub output: Vec<i32>,
+    pub memory: Vec<i32>,
+}
+
+// an implementation of the Intcode machine under test
+pub trait Engine {
+    fn name(&self) -> &str;
+
+    fn run(&self, program: &[i32], input: &[i32], steps: u64) -> Outcome;
+}
+
+// the interpreter in this crate
+pub struct Interpreter;
+
+impl Engine for Interpreter {
+    fn name(&self) -> &str {
+        "interpreter"
+    }
+
+    fn run(&self, program: &[i32], input: &[i32], steps: u64) -> Outcome {
+        let mut computer = Computer::new(program);
+
+        computer.input_buffer.extend(input.iter());
+        computer.with_step_limit(Some(steps));
+
+        let status = match computer.run() {
+            Ok(Signal::HALT) => Status::HALT,
+            Ok(Signal::INPUT) => Status::INPUT,
+            Ok(Signal::LIMIT) => Status::LIMIT,
+            Ok(_) => Status::INTERRUPTED,
+            Err(_) => Status::FAULT,
+        };
+
+        Outcome {
+            status,
+            output: computer.output_buffer.drain(..).collect(),
+            memory: computer.memory().to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaseResult {
+    pub name: String,
+    // a description of every way the outcome differed from the case
+    pub failures: Vec<String>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+pub fn check(case: &Case, engine: &dyn Engine) -> CaseResult {
+    let outcome = engine.run(&case.program, &case.input, case.steps);
+    let mut failures = Vec::new();
+
+    if outcome.status != case.status {
+        failures.push(format!("status {}, expected {}", outcome.status, case.status));
+    }
+
+    if outcome.output != case.output {
+        failures.push(format!(
+            "output [{}], expected [{}]",
+            program::to_text(&outcome.output),
+            program::to_text(&case.output),
+        ));
+    }
+
+    if let Some(memory) = case.memory.as_ref() {
+        if outcome.memory != *memory {
+            failures.push(format!(
+                "memory [{}], expected [{}]",
+                program::to_text(&outcome.memory),
+                program::to_text(memory),
+            ));
+        }
+    }
+
+    CaseResult { name: case.name.clone(), failures }
+}
+
+pub fn run_suite(cases: &[Case], engine: &dyn Engine) -> Vec<CaseResult> {
+    cases.iter()
+        .map(|case| check(case, engine))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpreter_passes_suite() {
+        let cases = load_suite(concat!(env!("CARGO_MANIFEST_DIR"), "/conformance")).unwrap();
+        assert!(!cases.is_empty());
+
+        let failures = run_suite(&cases, &Interpreter).into_iter()
+            .filter(|result| !result.passed())
+            .map(|result| format!("{}: {}", result.name, result.failures.join("; ")))
+            .collect::<Vec<String>>();
+
+        assert!(failures.is_empty(), "failed cases:\n{}", failures.join("\n"));
+    }
+
+    // an engine which always stops as though it had timed out
+    struct TimesOut;
+
+    impl Engine for TimesOut {
+        fn name(&self) -> &str {
+            "times out"
+        }
+
+        fn run(&self, program: &[i32], _: &[i32], _: u64) -> Outcome {
+            Outcome {
+                status: Status::INTERRUPTED,
+                output: Vec::new(),
+                memory: program.to_vec(),
+            }
+        }
+    }
+
+    #[test]
+    fn interruption_is_not_a_limit() {
+        let case = Case::parse("spin", "program: 1105,1,0\nstatus: limit\nsteps: 10").unwrap();
+        let result = check(&case, &TimesOut);
+
+        assert_eq!(result.failures, ["status interrupted, expected limit"]);
+        assert!("interrupted".parse::<Status>().is_err());
+    }
+}
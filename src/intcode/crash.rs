@@ -0,0 +1,237 @@
+use std::fmt;
+
+use super::disasm::{self, Line};
+use super::{Computer, Fault};
+
+// an executed instruction, with all of its words as they were when it ran
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub step: u64,
+    pub address: usize,
+    pub words: Vec<i32>,
+}
+
+// a ring buffer of the most recently executed instructions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct History {
+    traces: Vec<Trace>,
+    capacity: usize,
+    next: usize,
+    // the words of the instruction about to run, kept in a buffer which is
+    // swapped with that of the oldest trace so the history stops allocating
+    // once it is full
+    pending: Vec<i32>,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            traces: Vec::with_capacity(capacity),
+            capacity,
+            next: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    // copies the words of an instruction before it runs, so that push() can
+    // keep them once it has run
+    pub fn capture(&mut self, words: &[i32]) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.pending.clear();
+        self.pending.extend_from_slice(words);
+    }
+
+    // keeps the instruction last captured as having run at the given step
+    pub fn push(&mut self, step: u64, address: usize) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let words = std::mem::take(&mut self.pending);
+        let trace = Trace { step, address, words };
+
+        if self.traces.len() < self.capacity {
+            self.traces.push(trace);
+        } else {
+            let oldest = std::mem::replace(&mut self.traces[self.next], trace);
+            self.pending = oldest.words;
+        }
+
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    // oldest first
+    pub fn traces(&self) -> Vec<Trace> {
+        match self.traces.len() < self.capacity {
+            true => self.traces.clone(),
+            false => [&self.traces[self.next..], &self.traces[..self.next]].concat(),
+        }
+    }
+}
+
+// the number of words shown on either side of the faulting address
+const MEMORY_CONTEXT: usize = 16;
+const MEMORY_COLUMNS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct CrashReport {
+    pub fault: Fault,
+    pub steps: u64,
+    // None when the instruction pointer is outside memory
+    pub instruction: Option<Line>,
+    pub history: Vec<(Trace, Line)>,
+    pub memory_start: usize,
+    pub memory: Vec<i32>,
+    pub input_buffer: Vec<i32>,
+    pub output_buffer: Vec<i32>,
+}
+
+impl CrashReport {
+    pub fn new(computer: &Computer, fault: &Fault) -> Self {
+        let memory = &computer.memory;
+        let instruction_set = computer.instruction_set();
+        let address = fault.address();
+
+        let instruction = match address < memory.len() {
+            true => Some(disasm::decode(memory, address, instruction_set)),
+            false => None,
+        };
+
+        let history = computer.history().traces()
+            .into_iter()
+            .map(|trace| {
+                let mut line = disasm::decode(&trace.words, 0, instruction_set);
+                line.address = trace.address;
+                (trace, line)
+            })
+            .collect();
+
+        let start = address.min(memory.len()).saturating_sub(MEMORY_CONTEXT);
+        let start = start - start % MEMORY_COLUMNS;
+        let end = address.saturating_add(MEMORY_CONTEXT + 1).min(memory.len());
+
+        Self {
+            fault: fault.clone(),
+            steps: computer.steps(),
+            instruction,
+            history,
+            memory_start: start,
+            memory: memory[start..end.max(start)].to_vec(),
+            input_buffer: computer.input_buffer.iter().copied().collect(),
+            output_buffer: computer.output_buffer.iter().copied().collect(),
+        }
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} after {} steps", self.fault, self.steps)?;
+
+        writeln!(f, "\nfaulting instruction:")?;
+
+        match &self.instruction {
+            Some(line) => writeln!(f, "    {:>6}: {}", line.address, line.text)?,
+            None => writeln!(f, "    outside memory")?,
+        }
+
+        writeln!(f, "\nrecent instructions, oldest first:")?;
+
+        if self.history.is_empty() {
+            writeln!(f, "    none")?;
+        }
+
+        for (trace, line) in self.history.iter() {
+            writeln!(
+                f, "    step {:>8}  {:>6}: {}",
+                trace.step, line.address, line.text,
+            )?;
+        }
+
+        writeln!(f, "\nmemory around address {}:", self.fault.address())?;
+
+        for (row, words) in self.memory.chunks(MEMORY_COLUMNS).enumerate() {
+            let address = self.memory_start + row * MEMORY_COLUMNS;
+            write!(f, "    {:>6}:", address)?;
+
+            for (offset, word) in words.iter().enumerate() {
+                match address + offset == self.fault.address() {
+                    true => write!(f, " [{:>6}]", word)?,
+                    false => write!(f, "  {:>6} ", word)?,
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        writeln!(f, "\ninput buffer: {:?}", self.input_buffer)?;
+        write!(f, "output buffer: {:?}", self.output_buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{Effect, InstructionSet, Operation, Role};
+
+    // adds four values and outputs the sum, a wider instruction than any
+    // built-in one
+    struct Sum4;
+
+    impl Operation for Sum4 {
+        fn mnemonic(&self) -> &str {
+            "SUM4"
+        }
+
+        fn roles(&self) -> &[Role] {
+            &[Role::READ; 4]
+        }
+
+        fn execute(
+            &self,
+            computer: &mut Computer,
+            params: &[usize]
+        ) -> Result<Effect, Fault> {
+            let sum = params.iter().map(|&param| computer.read(param)).sum();
+            computer.send(sum);
+            Ok(Effect::NEXT)
+        }
+    }
+
+    #[test]
+    fn history_keeps_whole_instructions() {
+        let mut instruction_set = InstructionSet::default();
+        instruction_set.register(50, Sum4);
+
+        // SUM4 with four immediate parameters, then an unknown opcode
+        let mut computer = Computer::new(&[11150, 1, 2, 3, 4, 98]);
+        computer.with_instruction_set(instruction_set);
+
+        let fault = computer.run().unwrap_err();
+        let report = computer.crash_report(&fault);
+
+        let [(trace, line)] = &report.history[..] else {
+            panic!("expected one instruction in the history: {:?}", report.history);
+        };
+
+        assert_eq!(trace.words, [11150, 1, 2, 3, 4]);
+        let expected = disasm::decode(computer.memory(), 0, computer.instruction_set());
+        assert_eq!(line.text, expected.text);
+        assert!(line.text.starts_with("SUM4"), "{}", line.text);
+    }
+
+    #[test]
+    fn history_keeps_the_most_recent() {
+        let mut history = History::new(2);
+
+        for step in 0..3 {
+            history.capture(&[step as i32, 0]);
+            history.push(step, step as usize * 2);
+        }
+
+        let steps = history.traces().iter().map(|trace| trace.words[0]).collect::<Vec<i32>>();
+        assert_eq!(steps, [1, 2]);
+    }
+}
@@ -0,0 +1,802 @@
+// a Debug Adapter Protocol server which drives a Computer, presenting the
+// disassembled program as its source
+//
+// https://microsoft.github.io/debug-adapter-protocol/specification
+
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use crate::json::{self, Value};
+
+use super::{disasm, program, Computer, Fault, Signal};
+
+const THREAD_ID: i64 = 1;
+const FRAME_ID: i64 = 1;
+const SOURCE_REFERENCE: i64 = 1;
+
+const REGISTERS_REFERENCE: i64 = 1;
+const MEMORY_REFERENCE: i64 = 2;
+
+// how many instructions to execute between checks for a pause request
+const CHUNK_SIZE: usize = 10_000;
+
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+
+    loop {
+        let mut line = String::new();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(content_length) = content_length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        ));
+    };
+
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+
+    let content = String::from_utf8(content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    json::parse(&content)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+    writer.flush()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Resume {
+    CONTINUE,
+    STEP,
+}
+
+enum State {
+    IDLE,
+    STOPPED,
+    FAULTED(Fault),
+    TERMINATED,
+}
+
+struct Session<W: Write> {
+    writer: W,
+    receiver: Receiver<Value>,
+    pending: VecDeque<Value>,
+    seq: i64,
+    name: String,
+    computer: Option<Computer>,
+    lines: Vec<disasm::Line>,
+    source_breakpoints: BTreeSet<usize>,
+    instruction_breakpoints: BTreeSet<usize>,
+    stop_on_entry: bool,
+    state: State,
+}
+
+fn argument<'a>(request: &'a Value, key: &str) -> Option<&'a Value> {
+    request.get("arguments").and_then(|arguments| arguments.get(key))
+}
+
+fn parse_address(text: &str) -> Option<usize> {
+    let text = text.trim();
+
+    match text.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse::<usize>().ok(),
+    }
+}
+
+impl<W: Write> Session<W> {
+    fn new(writer: W, receiver: Receiver<Value>) -> Self {
+        Self {
+            writer,
+            receiver,
+            pending: VecDeque::new(),
+            seq: 0,
+            name: String::new(),
+            computer: None,
+            lines: Vec::new(),
+            source_breakpoints: BTreeSet::new(),
+            instruction_breakpoints: BTreeSet::new(),
+            stop_on_entry: false,
+            state: State::IDLE,
+        }
+    }
+
+    fn send(&mut self, mut entries: Vec<(&str, Value)>) -> io::Result<()> {
+        self.seq += 1;
+        entries.push(("seq", self.seq.into()));
+        write_message(&mut self.writer, &Value::object(entries))
+    }
+
+    fn respond(&mut self, request: &Value, body: Value) -> io::Result<()> {
+        let request_seq = request.get("seq").cloned().unwrap_or(Value::NULL);
+        let command = request.get("command").cloned().unwrap_or(Value::NULL);
+
+        self.send(vec![
+            ("type", "response".into()),
+            ("request_seq", request_seq),
+            ("command", command),
+            ("success", true.into()),
+            ("body", body),
+        ])
+    }
+
+    fn fail(&mut self, request: &Value, message: &str) -> io::Result<()> {
+        let request_seq = request.get("seq").cloned().unwrap_or(Value::NULL);
+        let command = request.get("command").cloned().unwrap_or(Value::NULL);
+
+        self.send(vec![
+            ("type", "response".into()),
+            ("request_seq", request_seq),
+            ("command", command),
+            ("success", false.into()),
+            ("message", message.into()),
+        ])
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> io::Result<()> {
+        self.send(vec![
+            ("type", "event".into()),
+            ("event", event.into()),
+            ("body", body),
+        ])
+    }
+
+    fn stopped(&mut self, reason: &str, description: &str) -> io::Result<()> {
+        self.state = State::STOPPED;
+
+        self.event("stopped", Value::object(vec![
+            ("reason", reason.into()),
+            ("description", description.into()),
+            ("threadId", THREAD_ID.into()),
+            ("allThreadsStopped", true.into()),
+        ]))
+    }
+
+    fn flush_output(&mut self) -> io::Result<()> {
+        let Some(computer) = self.computer.as_mut() else {
+            return Ok(());
+        };
+
+        let output = computer.output_buffer.drain(..)
+            .map(|value| format!("{}\n", value))
+            .collect::<String>();
+
+        if output.is_empty() {
+            return Ok(());
+        }
+
+        self.event("output", Value::object(vec![
+            ("category", "stdout".into()),
+            ("output", output.into()),
+        ]))
+    }
+
+    // the index of the source line covering an address
+    fn line_index(&self, address: usize) -> usize {
+        match self.lines.binary_search_by_key(&address, |line| line.address) {
+            Ok(index) => index,
+            Err(index) => index.saturating_sub(1),
+        }
+    }
+
+    fn source(&self) -> Value {
+        Value::object(vec![
+            ("name", format!("{}.disasm", self.name).into()),
+            ("sourceReference", SOURCE_REFERENCE.into()),
+        ])
+    }
+
+    fn source_text(&self) -> String {
+        self.lines.iter()
+            .map(|line| format!("{:>5}: {}\n", line.address, line.text))
+            .collect()
+    }
+
+    fn is_breakpoint(&self, address: usize) -> bool {
+        self.source_breakpoints.contains(&address)
+            || self.instruction_breakpoints.contains(&address)
+    }
+
+    // returns false once the client has disconnected
+    fn handle(&mut self, request: &Value) -> io::Result<bool> {
+        let command = request.get("command")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+
+        match command.as_str() {
+            "initialize" => {
+                self.respond(request, Value::object(vec![
+                    ("supportsConfigurationDoneRequest", true.into()),
+                    ("supportsInstructionBreakpoints", true.into()),
+                    ("supportsDisassembleRequest", true.into()),
+                    ("supportsSteppingGranularity", true.into()),
+                    ("supportsSetVariable", true.into()),
+                ]))?;
+                self.event("initialized", Value::object(Vec::<(&str, Value)>::new()))?;
+            },
+            "launch" => self.launch(request)?,
+            "setBreakpoints" => self.set_breakpoints(request)?,
+            "setInstructionBreakpoints" => self.set_instruction_breakpoints(request)?,
+            "setExceptionBreakpoints" => {
+                self.respond(request, Value::object(vec![
+                    ("breakpoints", Value::ARRAY(Vec::new())),
+                ]))?;
+            },
+            "configurationDone" => {
+                self.respond(request, Value::NULL)?;
+
+                if self.stop_on_entry {
+                    self.stopped("entry", "Stopped on entry")?;
+                } else {
+                    self.resume(Resume::CONTINUE)?;
+                }
+            },
+            "threads" => {
+                self.respond(request, Value::object(vec![
+                    ("threads", Value::ARRAY(vec![Value::object(vec![
+                        ("id", THREAD_ID.into()),
+                        ("name", "intcode".into()),
+                    ])])),
+                ]))?;
+            },
+            "stackTrace" => self.stack_trace(request)?,
+            "scopes" => self.scopes(request)?,
+            "variables" => self.variables(request)?,
+            "setVariable" => self.set_variable(request)?,
+            "source" => {
+                let content = self.source_text();
+                self.respond(request, Value::object(vec![
+                    ("content", content.into()),
+                    ("mimeType", "text/x-intcode-disassembly".into()),
+                ]))?;
+            },
+            "disassemble" => self.disassemble(request)?,
+            "evaluate" => self.evaluate(request)?,
+            "continue" => {
+                self.respond(request, Value::object(vec![
+                    ("allThreadsContinued", true.into()),
+                ]))?;
+                self.resume(Resume::CONTINUE)?;
+            },
+            "next" | "stepIn" | "stepOut" => {
+                self.respond(request, Value::NULL)?;
+                self.resume(Resume::STEP)?;
+            },
+            "pause" => {
+                self.respond(request, Value::NULL)?;
+                if let State::IDLE = self.state {
+                    self.stopped("pause", "Paused")?;
+                }
+            },
+            "disconnect" | "terminate" => {
+                self.respond(request, Value::NULL)?;
+                return Ok(false);
+            },
+            _ => self.fail(request, &format!("unsupported command: {}", command))?,
+        }
+
+        Ok(true)
+    }
+
+    fn launch(&mut self, request: &Value) -> io::Result<()> {
+        let Some(path) = argument(request, "program").and_then(Value::as_str) else {
+            return self.fail(request, "launch requires a program path");
+        };
+
+        let memory = match program::load(path) {
+            Ok(memory) => memory,
+            Err(e) => {
+                let message = format!("failed to load {}: {}", path, e);
+                return self.fail(request, &message);
+            },
+        };
+
+        let mut computer = Computer::new(&memory);
+
+        let inputs = argument(request, "inputs")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        for input in inputs.iter() {
+            match input.as_i64() {
+                Some(value) => computer.input_buffer.push_back(value as i32),
+                None => return self.fail(request, "inputs must be integers"),
+            }
+        }
+
+        self.name = path.rsplit(['/', '\\']).next().unwrap_or(path).to_string();
+        self.lines = disasm::disassemble(&computer.memory, computer.instruction_set());
+        self.computer = Some(computer);
+        self.stop_on_entry = argument(request, "stopOnEntry")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        self.respond(request, Value::NULL)
+    }
+
+    fn set_breakpoints(&mut self, request: &Value) -> io::Result<()> {
+        let requested = argument(request, "breakpoints")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        self.source_breakpoints.clear();
+
+        let mut breakpoints = Vec::new();
+
+        for (id, breakpoint) in requested.iter().enumerate() {
+            let line = breakpoint.get("line").and_then(Value::as_i64).unwrap_or(0);
+
+            let address = match line {
+                1.. => self.lines.get(line as usize - 1).map(|line| line.address),
+                _ => None,
+            };
+
+            if let Some(address) = address {
+                self.source_breakpoints.insert(address);
+            }
+
+            breakpoints.push(Value::object(vec![
+                ("id", (id + 1).into()),
+                ("verified", address.is_some().into()),
+                ("line", line.into()),
+            ]));
+        }
+
+        self.respond(request, Value::object(vec![
+            ("breakpoints", Value::ARRAY(breakpoints)),
+        ]))
+    }
+
+    fn set_instruction_breakpoints(&mut self, request: &Value) -> io::Result<()> {
+        let requested = argument(request, "breakpoints")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        self.instruction_breakpoints.clear();
+
+        let mut breakpoints = Vec::new();
+
+        for breakpoint in requested.iter() {
+            let reference = breakpoint.get("instructionReference")
+                .and_then(Value::as_str)
+                .and_then(parse_address);
+
+            let offset = breakpoint.get("offset")
+                .and_then(Value::as_i64)
+                .unwrap_or(0);
+
+            let address = reference
+                .map(|address| address as i64 + offset)
+                .filter(|address| *address >= 0)
+                .map(|address| address as usize);
+
+            if let Some(address) = address {
+                self.instruction_breakpoints.insert(address);
+            }
+
+            breakpoints.push(Value::object(vec![
+                ("verified", address.is_some().into()),
+                ("instructionReference", address.unwrap_or(0).to_string().into()),
+            ]));
+        }
+
+        self.respond(request, Value::object(vec![
+            ("breakpoints", Value::ARRAY(breakpoints)),
+        ]))
+    }
+
+    fn stack_trace(&mut self, request: &Value) -> io::Result<()> {
+        let Some(computer) = self.computer.as_ref() else {
+            return self.fail(request, "no program has been launched");
+        };
+
+        let ip = computer.instruction_pointer();
+        let line = self.line_index(ip) + 1;
+
+        let name = match ip < computer.memory.len() {
+            true => disasm::decode(&computer.memory, ip, computer.instruction_set()).text,
+            false => "<out of range>".to_string(),
+        };
+
+        let frame = Value::object(vec![
+            ("id", FRAME_ID.into()),
+            ("name", format!("{}: {}", ip, name).into()),
+            ("source", self.source()),
+            ("line", line.into()),
+            ("column", 1.into()),
+            ("instructionPointerReference", ip.to_string().into()),
+        ]);
+
+        self.respond(request, Value::object(vec![
+            ("stackFrames", Value::ARRAY(vec![frame])),
+            ("totalFrames", 1.into()),
+        ]))
+    }
+
+    fn scopes(&mut self, request: &Value) -> io::Result<()> {
+        let size = self.computer.as_ref()
+            .map(|computer| computer.memory.len())
+            .unwrap_or(0);
+
+        self.respond(request, Value::object(vec![
+            ("scopes", Value::ARRAY(vec![
+                Value::object(vec![
+                    ("name", "Registers".into()),
+                    ("presentationHint", "registers".into()),
+                    ("variablesReference", REGISTERS_REFERENCE.into()),
+                    ("expensive", false.into()),
+                ]),
+                Value::object(vec![
+                    ("name", "Memory".into()),
+                    ("variablesReference", MEMORY_REFERENCE.into()),
+                    ("indexedVariables", size.into()),
+                    ("expensive", true.into()),
+                ]),
+            ])),
+        ]))
+    }
+
+    fn variables(&mut self, request: &Value) -> io::Result<()> {
+        let Some(computer) = self.computer.as_ref() else {
+            return self.fail(request, "no program has been launched");
+        };
+
+        fn variable(name: String, value: String) -> Value {
+            Value::object(vec![
+                ("name", name.into()),
+                ("value", value.into()),
+                ("variablesReference", 0.into()),
+            ])
+        }
+
+        fn join(values: impl Iterator<Item = i32>) -> String {
+            values.map(|value| value.to_string()).collect::<Vec<String>>().join(", ")
+        }
+
+        let reference = argument(request, "variablesReference")
+            .and_then(Value::as_i64)
+            .unwrap_or(0);
+
+        let variables = match reference {
+            REGISTERS_REFERENCE => {
+                let status = match &self.state {
+                    State::FAULTED(fault) => format!("fault: {}", fault),
+                    State::TERMINATED => "halted".to_string(),
+                    _ => "running".to_string(),
+                };
+
+                vec![
+                    variable("ip".into(), computer.instruction_pointer().to_string()),
+                    variable("steps".into(), computer.steps().to_string()),
+                    variable("status".into(), status),
+                    variable("input".into(), format!("[{}]", join(computer.input_buffer.iter().copied()))),
+                ]
+            },
+            MEMORY_REFERENCE => {
+                let start = argument(request, "start")
+                    .and_then(Value::as_i64)
+                    .unwrap_or(0)
+                    .max(0) as usize;
+
+                let count = argument(request, "count")
+                    .and_then(Value::as_i64)
+                    .map(|count| count as usize)
+                    .unwrap_or(computer.memory.len());
+
+                computer.memory.iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(count)
+                    .map(|(address, value)| {
+                        variable(format!("[{}]", address), value.to_string())
+                    })
+                    .collect()
+            },
+            _ => Vec::new(),
+        };
+
+        self.respond(request, Value::object(vec![
+            ("variables", Value::ARRAY(variables)),
+        ]))
+    }
+
+    fn set_variable(&mut self, request: &Value) -> io::Result<()> {
+        let name = argument(request, "name").and_then(Value::as_str).unwrap_or("");
+        let value = argument(request, "value").and_then(Value::as_str).unwrap_or("");
+
+        let Some(computer) = self.computer.as_mut() else {
+            return self.fail(request, "no program has been launched");
+        };
+
+        let Ok(value) = value.trim().parse::<i32>() else {
+            return self.fail(request, "value must be an integer");
+        };
+
+        let address = name.strip_prefix('[')
+            .and_then(|name| name.strip_suffix(']'))
+            .and_then(parse_address)
+            .filter(|address| *address < computer.memory.len());
+
+        match address {
+            Some(address) => computer.write(address, value),
+            None => return self.fail(request, &format!("cannot set {}", name)),
+        }
+
+        self.respond(request, Value::object(vec![
+            ("value", value.to_string().into()),
+        ]))
+    }
+
+    fn disassemble(&mut self, request: &Value) -> io::Result<()> {
+        let Some(computer) = self.computer.as_ref() else {
+            return self.fail(request, "no program has been launched");
+        };
+
+        let reference = argument(request, "memoryReference")
+            .and_then(Value::as_str)
+            .and_then(parse_address)
+            .unwrap_or(0);
+
+        let offset = argument(request, "offset").and_then(Value::as_i64).unwrap_or(0);
+        let instruction_offset = argument(request, "instructionOffset")
+            .and_then(Value::as_i64)
+            .unwrap_or(0);
+        let count = argument(request, "instructionCount")
+            .and_then(Value::as_i64)
+            .unwrap_or(0)
+            .max(0);
+
+        let lines = disasm::disassemble(&computer.memory, computer.instruction_set());
+
+        let address = (reference as i64 + offset).max(0) as usize;
+        let base = match lines.binary_search_by_key(&address, |line| line.address) {
+            Ok(index) => index,
+            Err(index) => index.saturating_sub(1),
+        } as i64;
+
+        let instructions = (0..count)
+            .map(|index| base + instruction_offset + index)
+            .map(|index| {
+                let line = usize::try_from(index).ok().and_then(|index| lines.get(index));
+
+                match line {
+                    Some(line) => {
+                        let words = computer.memory[line.address..line.address + line.size]
+                            .iter()
+                            .map(|word| word.to_string())
+                            .collect::<Vec<String>>()
+                            .join(",");
+
+                        Value::object(vec![
+                            ("address", line.address.to_string().into()),
+                            ("instruction", line.text.clone().into()),
+                            ("instructionBytes", words.into()),
+                            ("line", (self.line_index(line.address) + 1).into()),
+                            ("location", self.source()),
+                        ])
+                    },
+                    None => Value::object(vec![
+                        ("address", index.to_string().into()),
+                        ("instruction", "??".into()),
+                        ("presentationHint", "invalid".into()),
+                    ]),
+                }
+            })
+            .collect::<Vec<Value>>();
+
+        self.respond(request, Value::object(vec![
+            ("instructions", Value::ARRAY(instructions)),
+        ]))
+    }
+
+    // the debug console accepts 'input v...' to feed the program and '[addr]'
+    // to inspect memory
+    fn evaluate(&mut self, request: &Value) -> io::Result<()> {
+        let expression = argument(request, "expression")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .trim()
+            .to_string();
+
+        let Some(computer) = self.computer.as_mut() else {
+            return self.fail(request, "no program has been launched");
+        };
+
+        let result = if let Some(values) = expression.strip_prefix("input") {
+            let values = values.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|value| !value.is_empty())
+                .map(|value| value.parse::<i32>())
+                .collect::<Result<Vec<i32>, _>>();
+
+            match values {
+                Ok(values) => {
+                    computer.input_buffer.extend(values.iter());
+                    Ok(format!("queued {} input(s)", values.len()))
+                },
+                Err(_) => Err("inputs must be integers".to_string()),
+            }
+        } else {
+            let address = expression.strip_prefix('[')
+                .and_then(|expression| expression.strip_suffix(']'))
+                .or(Some(expression.as_str()))
+                .and_then(parse_address);
+
+            match address.and_then(|address| computer.memory.get(address)) {
+                Some(value) => Ok(value.to_string()),
+                None => Err(format!("cannot evaluate {}", expression)),
+            }
+        };
+
+        match result {
+            Ok(result) => self.respond(request, Value::object(vec![
+                ("result", result.into()),
+                ("variablesReference", 0.into()),
+            ])),
+            Err(message) => self.fail(request, &message),
+        }
+    }
+
+    // returns true if a pause request arrived; other requests are handled
+    // once execution stops
+    fn poll_pause(&mut self) -> io::Result<bool> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(message) => {
+                    let command = message.get("command").and_then(Value::as_str);
+
+                    if command == Some("pause") {
+                        self.respond(&message, Value::NULL)?;
+                        return Ok(true);
+                    }
+
+                    self.pending.push_back(message);
+                },
+                Err(TryRecvError::Empty) => return Ok(false),
+                Err(TryRecvError::Disconnected) => return Ok(true),
+            }
+        }
+    }
+
+    fn resume(&mut self, resume: Resume) -> io::Result<()> {
+        if self.computer.is_none() {
+            return Ok(());
+        }
+
+        // a faulted program may be resumed, since the fault can be fixed up
+        // by editing memory first
+        if let State::TERMINATED = self.state {
+            return self.event("terminated", Value::object(Vec::<(&str, Value)>::new()));
+        }
+
+        self.state = State::IDLE;
+
+        let mut first = true;
+
+        loop {
+            for _ in 0..CHUNK_SIZE {
+                let ip = self.computer.as_ref().unwrap().instruction_pointer();
+
+                // a breakpoint on the instruction being resumed from must not
+                // stop execution again straight away
+                if !first && self.is_breakpoint(ip) {
+                    self.flush_output()?;
+                    return self.stopped("breakpoint", "Paused on breakpoint");
+                }
+
+                let result = self.computer.as_mut().unwrap().execute_instruction();
+                first = false;
+
+                match result {
+                    Ok(None) => (),
+                    Ok(Some(Signal::INPUT)) => {
+                        self.flush_output()?;
+                        return self.stopped("pause", "Waiting for input");
+                    },
+                    Ok(Some(_)) => {
+                        self.flush_output()?;
+                        self.state = State::TERMINATED;
+                        self.event("exited", Value::object(vec![("exitCode", 0.into())]))?;
+                        return self.event("terminated", Value::object(Vec::<(&str, Value)>::new()));
+                    },
+                    Err(fault) => {
+                        self.flush_output()?;
+                        let description = fault.to_string();
+                        self.stopped("exception", &description)?;
+                        self.state = State::FAULTED(fault);
+                        return Ok(());
+                    },
+                }
+
+                if resume == Resume::STEP {
+                    self.flush_output()?;
+                    return self.stopped("step", "Stepped");
+                }
+            }
+
+            self.flush_output()?;
+
+            if self.poll_pause()? {
+                return self.stopped("pause", "Paused");
+            }
+        }
+    }
+}
+
+pub fn serve<R, W>(reader: R, writer: W) -> io::Result<()>
+where
+    R: Read + Send + 'static,
+    W: Write,
+{
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+
+        while let Ok(Some(message)) = read_message(&mut reader) {
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut session = Session::new(writer, receiver);
+
+    loop {
+        let message = match session.pending.pop_front() {
+            Some(message) => message,
+            None => match session.receiver.recv() {
+                Ok(message) => message,
+                Err(_) => break,
+            },
+        };
+
+        if !session.handle(&message)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn serve_stdio() -> io::Result<()> {
+    serve(io::stdin(), io::stdout())
+}
+
+// accepts a single client on a localhost port
+pub fn serve_tcp(port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    eprintln!("listening on {}", listener.local_addr()?);
+
+    let (stream, _) = listener.accept()?;
+    serve(stream.try_clone()?, stream)
+}
@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::{Computer, Fault, Signal};
+
+// a fixed-size group of outputs, such as an (x, y, tile) triple
+pub trait Record: Sized {
+    const SIZE: usize;
+
+    // called with exactly SIZE values
+    fn decode(values: &[i32]) -> Self;
+}
+
+impl Record for i32 {
+    const SIZE: usize = 1;
+
+    fn decode(values: &[i32]) -> Self {
+        values[0]
+    }
+}
+
+impl Record for (i32, i32) {
+    const SIZE: usize = 2;
+
+    fn decode(values: &[i32]) -> Self {
+        (values[0], values[1])
+    }
+}
+
+impl Record for (i32, i32, i32) {
+    const SIZE: usize = 3;
+
+    fn decode(values: &[i32]) -> Self {
+        (values[0], values[1], values[2])
+    }
+}
+
+impl<const N: usize> Record for [i32; N] {
+    // an empty record would never consume any output, so reading [i32; 0]
+    // records fails to compile rather than looping forever
+    const SIZE: usize = const {
+        assert!(N > 0, "records must hold at least one value");
+        N
+    };
+
+    fn decode(values: &[i32]) -> Self {
+        values.try_into().unwrap()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    // the program stopped producing output part way through a group
+    PARTIAL { size: usize, values: Vec<i32> },
+    FAULT(Fault),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::PARTIAL { size, values } => write!(
+                f,
+                "trailing partial group of {} values, expected {}: {:?}",
+                values.len(), size, values,
+            ),
+            Self::FAULT(fault) => write!(f, "{}", fault),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<Fault> for DecodeError {
+    fn from(fault: Fault) -> Self {
+        Self::FAULT(fault)
+    }
+}
+
+pub fn decode<R: Record>(values: &[i32]) -> Result<Vec<R>, DecodeError> {
+    let remainder = values.len() % R::SIZE;
+
+    if remainder != 0 {
+        return Err(DecodeError::PARTIAL {
+            size: R::SIZE,
+            values: values[values.len() - remainder..].to_vec(),
+        });
+    }
+
+    Ok(values.chunks(R::SIZE).map(R::decode).collect())
+}
+
+fn pop_record<R: Record>(buffer: &mut VecDeque<i32>) -> Option<R> {
+    if buffer.len() < R::SIZE {
+        return None;
+    }
+
+    let values = buffer.drain(..R::SIZE).collect::<Vec<i32>>();
+    Some(R::decode(&values))
+}
+
+// runs the computer one instruction at a time, yielding each record as soon
+// as its last value is output
+//
+// iteration ends when the program halts, faults, waits for input or is
+// stopped by the computer's step limit, cancellation or time budget; unless
+// it halted or faulted, any values of an incomplete group stay in the output
+// buffer, so the caller can provide input or raise the limit through
+// computer() and keep iterating
+pub struct Records<'a, R: Record> {
+    computer: &'a mut Computer,
+    finished: bool,
+    record: PhantomData<R>,
+}
+
+impl<'a, R: Record> Records<'a, R> {
+    pub fn new(computer: &'a mut Computer) -> Self {
+        Self {
+            computer,
+            finished: false,
+            record: PhantomData,
+        }
+    }
+
+    pub fn computer(&mut self) -> &mut Computer {
+        self.computer
+    }
+}
+
+impl<R: Record> Iterator for Records<'_, R> {
+    type Item = Result<R, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = pop_record(&mut self.computer.output_buffer) {
+                return Some(Ok(record));
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            match self.computer.step() {
+                Ok(None) => (),
+                Ok(Some(Signal::HALT)) => {
+                    self.finished = true;
+
+                    if self.computer.output_buffer.is_empty() {
+                        return None;
+                    }
+
+                    let values = self.computer.output_buffer.drain(..).collect();
+
+                    return Some(Err(DecodeError::PARTIAL {
+                        size: R::SIZE,
+                        values,
+                    }));
+                },
+                Ok(Some(_)) => return None,
+                Err(fault) => {
+                    self.finished = true;
+                    return Some(Err(fault.into()));
+                },
+            }
+        }
+    }
+}
+
+// yields each output as soon as the OUTPUT instruction producing it has
+// executed, without running the program any further
+//
+// like Records, iteration pauses when the program waits for input or is
+// stopped early, and can be continued through computer()
+pub struct Outputs<'a> {
+    computer: &'a mut Computer,
+    finished: bool,
+}
+
+impl<'a> Outputs<'a> {
+    pub fn new(computer: &'a mut Computer) -> Self {
+        Self { computer, finished: false }
+    }
+
+    pub fn computer(&mut self) -> &mut Computer {
+        self.computer
+    }
+}
+
+impl Iterator for Outputs<'_> {
+    type Item = Result<i32, Fault>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.computer.output_buffer.pop_front() {
+                return Some(Ok(value));
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            match self.computer.step() {
+                Ok(None) => (),
+                Ok(Some(Signal::HALT)) => self.finished = true,
+                Ok(Some(_)) => return None,
+                Err(fault) => {
+                    self.finished = true;
+                    return Some(Err(fault));
+                },
+            }
+        }
+    }
+}
+
+impl Computer {
+    pub fn outputs(&mut self) -> Outputs<'_> {
+        Outputs::new(self)
+    }
+
+    pub fn records<R: Record>(&mut self) -> Records<'_, R> {
+        Records::new(self)
+    }
+
+    // decodes the whole output buffer, leaving it untouched if it does not
+    // hold a whole number of records
+    pub fn drain_records<R: Record>(&mut self) -> Result<Vec<R>, DecodeError> {
+        let records = decode(self.output_buffer.make_contiguous())?;
+        self.output_buffer.clear();
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::intcode::Cancellation;
+
+    // a program which jumps to itself forever without any output
+    const SPIN: [i32; 3] = [1105, 1, 0];
+
+    #[test]
+    fn outputs_stop_at_step_limit() {
+        let mut computer = Computer::new(&SPIN);
+        computer.with_step_limit(Some(1000));
+
+        assert!(computer.outputs().next().is_none());
+        assert_eq!(computer.steps(), 1000);
+    }
+
+    #[test]
+    fn outputs_stop_when_cancelled() {
+        let cancellation = Cancellation::new();
+        cancellation.cancel();
+
+        let mut computer = Computer::new(&SPIN);
+        computer.with_cancellation(Some(cancellation));
+
+        assert!(computer.outputs().next().is_none());
+        assert_eq!(computer.steps(), 0);
+    }
+
+    #[test]
+    fn records_stop_at_time_budget() {
+        let mut computer = Computer::new(&SPIN);
+        computer.with_time_budget(Some(Duration::from_millis(10)));
+
+        assert!(computer.records::<(i32, i32)>().next().is_none());
+    }
+}
@@ -0,0 +1,1369 @@
+// recovers C-like pseudocode from an Intcode program
+//
+// code is found by following control flow from address zero while tracking
+// the values the program stores: memory is assumed to start out as stored,
+// so jumps through cells holding a known address can be followed, branches
+// on known values are resolved, and code rewritten with known values is
+// decoded as it will run. words rewritten with values which are not known,
+// or which are not valid instructions, are kept as data and decoding carries
+// on after them
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use super::{Instruction, InstructionSet, OpCode, ParameterMode, Role};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operand {
+    IMM(i32),
+    MEM(usize),
+    // a parameter in position mode whose address is rewritten at runtime,
+    // which reads the cell named by the one holding the parameter
+    PTR(usize),
+}
+
+#[derive(Clone, Debug)]
+struct Decoded {
+    address: usize,
+    size: usize,
+    opcode: Option<OpCode>,
+    mnemonic: String,
+    operands: Vec<Operand>,
+    roles: Vec<Role>,
+    // words which could not be decoded, kept as they are
+    data: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    LT,
+    EQ,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Condition {
+    operand: Operand,
+    // the comparison which produced the operand, when it can be folded in
+    comparison: Option<(Comparison, Operand, Operand)>,
+    negated: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Terminator {
+    FALL(usize),
+    GOTO(usize),
+    BRANCH { condition: Condition, taken: usize, fallthrough: usize },
+    INDIRECT {
+        condition: Option<Condition>,
+        target: Operand,
+        fallthrough: Option<usize>,
+    },
+    HALT,
+    OUTSIDE(usize),
+}
+
+#[derive(Clone, Debug)]
+struct Block {
+    address: usize,
+    instructions: Vec<Decoded>,
+    terminator: Terminator,
+}
+
+impl Terminator {
+    fn successors(&self) -> Vec<usize> {
+        match self {
+            Self::FALL(next) => vec![*next],
+            Self::GOTO(target) => vec![*target],
+            Self::BRANCH { taken, fallthrough, .. } => vec![*taken, *fallthrough],
+            Self::INDIRECT { fallthrough, .. } => fallthrough.iter().copied().collect(),
+            Self::HALT | Self::OUTSIDE(_) => vec![],
+        }
+    }
+}
+
+// decodes the instruction at an address as it will be when it runs, so
+// parameters which are rewritten to unknown values read through the cell
+// holding them
+fn decode(
+    memory: &[i32],
+    cells: &Cells,
+    address: usize,
+    instruction_set: &InstructionSet
+) -> Option<Decoded> {
+    let word = cells.word(memory, address)?;
+    let instruction = Instruction::decode(word);
+    let operation = instruction_set.get(instruction.opcode_id)?;
+
+    if address + operation.arity() >= memory.len() {
+        return None;
+    }
+
+    let operands = (1..=operation.arity())
+        .map(|offset| {
+            let parameter = address + offset;
+
+            match (instruction.pmode(offset).ok()?, cells.word(memory, parameter)) {
+                (ParameterMode::POSITION, Some(value)) if value >= 0 => {
+                    Some(Operand::MEM(value as usize))
+                },
+                (ParameterMode::POSITION, Some(_)) => None,
+                (ParameterMode::POSITION, None) => Some(Operand::PTR(parameter)),
+                (ParameterMode::IMMEDIATE, Some(value)) => Some(Operand::IMM(value)),
+                (ParameterMode::IMMEDIATE, None) => Some(Operand::MEM(parameter)),
+            }
+        })
+        .collect::<Option<Vec<Operand>>>()?;
+
+    // only the built-in operations have known semantics; anything else that
+    // has been registered is shown as an opaque call
+    let opcode = OpCode::try_from(instruction.opcode_id).ok();
+
+    Some(Decoded {
+        address,
+        size: operation.size(),
+        opcode,
+        mnemonic: operation.mnemonic().to_string(),
+        operands,
+        roles: operation.roles().to_vec(),
+        data: false,
+    })
+}
+
+// how many instructions must decode after skipping words which do not, for
+// decoding to carry on from there, and the most words which are skipped
+const RESYNC: usize = 3;
+const SKIP: usize = 4;
+
+// a word which is not a valid instruction, along with as few of the words
+// after it as must be skipped for decoding to get back in step
+fn data(
+    memory: &[i32],
+    cells: &Cells,
+    address: usize,
+    instruction_set: &InstructionSet
+) -> Decoded {
+    let in_step = |mut address: usize| {
+        for _ in 0..RESYNC {
+            match decode(memory, cells, address, instruction_set) {
+                Some(instruction) => match instruction.opcode {
+                    Some(OpCode::JIT | OpCode::JIF | OpCode::HALT) => return true,
+                    _ => address += instruction.size,
+                },
+                None => return false,
+            }
+        }
+
+        true
+    };
+
+    let size = (1..=SKIP)
+        .take_while(|skip| address + skip < memory.len())
+        .find(|skip| in_step(address + skip))
+        .unwrap_or(1)
+        .min(memory.len() - address);
+
+    Decoded {
+        address,
+        size,
+        opcode: None,
+        mnemonic: "data".to_string(),
+        operands: memory[address..address + size].iter()
+            .map(|word| Operand::IMM(*word))
+            .collect(),
+        roles: Vec::new(),
+        data: true,
+    }
+}
+
+struct Program {
+    blocks: BTreeMap<usize, Block>,
+    // addresses covered by decoded instructions
+    code: BTreeSet<usize>,
+    // addresses which the program writes to
+    rewritten: BTreeSet<usize>,
+}
+
+// what is known about memory before an instruction runs, kept as the cells
+// which the program has written: Some for a value which every path to the
+// instruction stores there, and None for one which is not known. other cells
+// hold their stored values, unless something was written to an address which
+// is not known, after which only the code is assumed to be left as stored
+#[derive(Clone, Debug, Default)]
+struct Cells {
+    written: BTreeMap<usize, Option<i32>>,
+    clobbered: bool,
+}
+
+impl Cells {
+    fn get(&self, memory: &[i32], address: usize) -> Option<i32> {
+        match self.written.get(&address) {
+            Some(value) => *value,
+            None if self.clobbered => None,
+            None => memory.get(address).copied(),
+        }
+    }
+
+    // the word an instruction will be decoded from
+    fn word(&self, memory: &[i32], address: usize) -> Option<i32> {
+        match self.written.get(&address) {
+            Some(value) => *value,
+            None => memory.get(address).copied(),
+        }
+    }
+
+    fn set(&mut self, memory: &[i32], address: usize, value: Option<i32>) {
+        match !self.clobbered && value.is_some() && value == memory.get(address).copied() {
+            true => self.written.remove(&address),
+            false => self.written.insert(address, value),
+        };
+    }
+
+    fn clobber(&mut self) {
+        self.written.values_mut().for_each(|value| *value = None);
+        self.clobbered = true;
+    }
+
+    fn value(&self, memory: &[i32], operand: Operand) -> Option<i32> {
+        match operand {
+            Operand::IMM(value) => Some(value),
+            Operand::MEM(address) => self.get(memory, address),
+            Operand::PTR(parameter) => self.get(memory, parameter)
+                .and_then(|address| usize::try_from(address).ok())
+                .and_then(|address| self.get(memory, address)),
+        }
+    }
+
+    // forgets every value which differs along another path to the same
+    // instruction, returning true if anything was forgotten
+    fn join(&mut self, memory: &[i32], other: &Cells) -> bool {
+        let addresses = self.written.keys()
+            .chain(other.written.keys())
+            .copied()
+            .collect::<BTreeSet<usize>>();
+
+        let mut changed = false;
+
+        for address in addresses {
+            let value = self.get(memory, address);
+
+            if value.is_some() && value != other.get(memory, address) {
+                self.written.insert(address, None);
+                changed = true;
+            }
+        }
+
+        if other.clobbered && !self.clobbered {
+            self.clobber();
+            changed = true;
+        }
+
+        changed
+    }
+
+    // applies the writes of an instruction which does not jump
+    fn update(&mut self, memory: &[i32], instruction: &Decoded) {
+        let operands = &instruction.operands;
+        let value = |operand: Operand| self.value(memory, operand);
+
+        let (destination, result) = match instruction.opcode {
+            Some(opcode @ (OpCode::ADD | OpCode::MUL | OpCode::LT | OpCode::EQ)) => {
+                let result = value(operands[0]).zip(value(operands[1]))
+                    .and_then(|(lhs, rhs)| match opcode {
+                        OpCode::ADD => lhs.checked_add(rhs),
+                        OpCode::MUL => lhs.checked_mul(rhs),
+                        OpCode::LT => Some((lhs < rhs) as i32),
+                        _ => Some((lhs == rhs) as i32),
+                    });
+                (operands[2], result)
+            },
+            Some(OpCode::INPUT) => (operands[0], None),
+            Some(_) => return,
+            // nothing is known about what other operations write
+            None => {
+                for (operand, role) in operands.iter().zip(instruction.roles.iter()) {
+                    match (operand, role) {
+                        (Operand::MEM(address), Role::WRITE) => {
+                            self.set(memory, *address, None);
+                        },
+                        (Operand::PTR(_), Role::WRITE) => self.clobber(),
+                        _ => (),
+                    }
+                }
+                return;
+            },
+        };
+
+        match destination {
+            Operand::MEM(address) => self.set(memory, address, result),
+            Operand::PTR(_) => self.clobber(),
+            Operand::IMM(_) => (),
+        }
+    }
+}
+
+// whether a jump is always or never taken, and where it goes, as far as is
+// known from the values in memory before it
+fn resolve(
+    instruction: &Decoded,
+    cells: &Cells,
+    memory: &[i32]
+) -> (Option<bool>, Option<usize>) {
+    let [operand, target] = instruction.operands[..] else {
+        unreachable!();
+    };
+
+    let negated = instruction.opcode == Some(OpCode::JIF);
+
+    let always = cells.value(memory, operand).map(|value| (value != 0) != negated);
+
+    let target = cells.value(memory, target)
+        .filter(|target| *target >= 0)
+        .map(|target| target as usize);
+
+    (always, target)
+}
+
+fn discover(memory: &[i32], instruction_set: &InstructionSet) -> Program {
+    let mut decoded = BTreeMap::new();
+    let mut leaders = BTreeSet::from([0]);
+    let mut worklist = vec![0];
+    let mut states = HashMap::from([(0, Cells::default())]);
+
+    // jumps outside of memory are collected as empty blocks which trap
+    let mut outside = BTreeSet::new();
+
+    while let Some(address) = worklist.pop() {
+        if address >= memory.len() {
+            outside.insert(address);
+            continue;
+        }
+
+        let mut cells = states[&address].clone();
+
+        // instructions are decoded again whenever more paths reach them, as
+        // the code they run may have been rewritten along those paths
+        let instruction = decode(memory, &cells, address, instruction_set)
+            .unwrap_or_else(|| data(memory, &cells, address, instruction_set));
+
+        decoded.insert(address, instruction.clone());
+        let next = address + instruction.size;
+
+        let successors = match instruction.opcode {
+            Some(OpCode::JIT | OpCode::JIF) => {
+                let (always, target) = resolve(&instruction, &cells, memory);
+                let mut successors = Vec::new();
+
+                if always != Some(false) {
+                    if let Some(target) = target {
+                        leaders.insert(target);
+                        successors.push(target);
+                    }
+                }
+
+                if always != Some(true) {
+                    leaders.insert(next);
+                    successors.push(next);
+                }
+
+                successors
+            },
+            Some(OpCode::HALT) => Vec::new(),
+            _ => {
+                cells.update(memory, &instruction);
+                vec![next]
+            },
+        };
+
+        for successor in successors {
+            if successor >= memory.len() {
+                outside.insert(successor);
+                continue;
+            }
+
+            let changed = match states.get_mut(&successor) {
+                Some(state) => state.join(memory, &cells),
+                None => {
+                    states.insert(successor, cells.clone());
+                    true
+                },
+            };
+
+            if changed {
+                worklist.push(successor);
+            }
+        }
+    }
+
+    let mut blocks = BTreeMap::new();
+    let mut ends = HashMap::new();
+
+    for &leader in leaders.iter() {
+        if outside.contains(&leader) || leader >= memory.len() {
+            blocks.insert(leader, Block {
+                address: leader,
+                instructions: Vec::new(),
+                terminator: Terminator::OUTSIDE(leader),
+            });
+            continue;
+        }
+
+        let mut instructions = Vec::new();
+        let mut address = leader;
+
+        let terminator = loop {
+            let Some(instruction) = decoded.get(&address) else {
+                break Terminator::OUTSIDE(address);
+            };
+
+            let next = address + instruction.size;
+            address = next;
+
+            match instruction.opcode {
+                Some(opcode @ (OpCode::JIT | OpCode::JIF)) => {
+                    let state = &states[&instruction.address];
+                    let (always, target_block) = resolve(instruction, state, memory);
+
+                    let condition = Condition {
+                        operand: instruction.operands[0],
+                        comparison: None,
+                        negated: opcode == OpCode::JIF,
+                    };
+
+                    let target = instruction.operands[1];
+
+                    break match (always, target_block) {
+                        (Some(false), _) => Terminator::FALL(next),
+                        (Some(true), Some(target)) => Terminator::GOTO(target),
+                        (Some(true), None) => Terminator::INDIRECT {
+                            condition: None,
+                            target,
+                            fallthrough: None,
+                        },
+                        (None, Some(target)) => Terminator::BRANCH {
+                            condition,
+                            taken: target,
+                            fallthrough: next,
+                        },
+                        (None, None) => Terminator::INDIRECT {
+                            condition: Some(condition),
+                            target,
+                            fallthrough: Some(next),
+                        },
+                    };
+                },
+                Some(OpCode::HALT) => break Terminator::HALT,
+                _ => {
+                    instructions.push(instruction.clone());
+
+                    if leaders.contains(&next) {
+                        break Terminator::FALL(next);
+                    }
+                },
+            }
+        };
+
+        ends.insert(leader, address);
+        blocks.insert(leader, Block { address: leader, instructions, terminator });
+    }
+
+    // a jump resolved early on may have become unknown once more paths
+    // reached it, leaving blocks which nothing jumps to any more
+    let mut reachable = HashSet::from([0]);
+    let mut worklist = vec![0];
+
+    while let Some(address) = worklist.pop() {
+        for successor in blocks[&address].terminator.successors() {
+            if reachable.insert(successor) {
+                worklist.push(successor);
+            }
+        }
+    }
+
+    blocks.retain(|address, _| reachable.contains(address));
+
+    let code = blocks.keys()
+        .filter_map(|leader| ends.get(leader).map(|end| *leader..*end))
+        .flatten()
+        .collect::<BTreeSet<usize>>();
+
+    let rewritten = blocks.values()
+        .flat_map(|block| block.instructions.iter())
+        .filter_map(|instruction| reads_writes(instruction).1)
+        .collect::<BTreeSet<usize>>();
+
+    Program { blocks, code, rewritten }
+}
+
+// the cells an instruction reads and the one it writes, if that is known; a
+// destination which is rewritten at runtime reads the cell holding it
+fn reads_writes(instruction: &Decoded) -> (Vec<usize>, Option<usize>) {
+    let cells = |operands: &[Operand]| {
+        operands.iter()
+            .filter_map(|operand| match operand {
+                Operand::MEM(address) | Operand::PTR(address) => Some(*address),
+                Operand::IMM(_) => None,
+            })
+            .collect::<Vec<usize>>()
+    };
+
+    let (reads, destination) = match instruction.opcode {
+        Some(OpCode::ADD | OpCode::MUL | OpCode::LT | OpCode::EQ) => {
+            (cells(&instruction.operands[..2]), instruction.operands[2])
+        },
+        Some(OpCode::INPUT) => (Vec::new(), instruction.operands[0]),
+        _ => return (cells(&instruction.operands), None),
+    };
+
+    match destination {
+        Operand::MEM(address) => (reads, Some(address)),
+        Operand::PTR(parameter) => ([reads, vec![parameter]].concat(), None),
+        Operand::IMM(_) => (reads, None),
+    }
+}
+
+impl Program {
+    fn predecessors(&self) -> HashMap<usize, Vec<usize>> {
+        let mut predecessors = HashMap::<usize, Vec<usize>>::new();
+
+        for block in self.blocks.values() {
+            for successor in block.terminator.successors() {
+                predecessors.entry(successor).or_default().push(block.address);
+            }
+        }
+
+        predecessors
+    }
+
+    fn dominators(&self) -> HashMap<usize, BTreeSet<usize>> {
+        let all = self.blocks.keys().copied().collect::<BTreeSet<usize>>();
+        let predecessors = self.predecessors();
+
+        let mut dominators = self.blocks.keys()
+            .map(|&address| match address {
+                0 => (address, BTreeSet::from([0])),
+                _ => (address, all.clone()),
+            })
+            .collect::<HashMap<usize, BTreeSet<usize>>>();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for &address in self.blocks.keys().filter(|&&address| address != 0) {
+                let mut set = predecessors.get(&address)
+                    .into_iter()
+                    .flatten()
+                    .map(|predecessor| dominators[predecessor].clone())
+                    .reduce(|a, b| a.intersection(&b).copied().collect())
+                    .unwrap_or_default();
+
+                set.insert(address);
+
+                if set != dominators[&address] {
+                    dominators.insert(address, set);
+                    changed = true;
+                }
+            }
+        }
+
+        dominators
+    }
+
+    // the block every path from a block must pass through next, if any
+    fn immediate_postdominators(&self) -> HashMap<usize, Option<usize>> {
+        const EXIT: usize = usize::MAX;
+
+        let mut all = self.blocks.keys().copied().collect::<BTreeSet<usize>>();
+        all.insert(EXIT);
+
+        let successors = |address: usize| {
+            let successors = self.blocks[&address].terminator.successors();
+            match successors.is_empty() {
+                true => vec![EXIT],
+                false => successors,
+            }
+        };
+
+        let mut postdominators = self.blocks.keys()
+            .map(|&address| (address, all.clone()))
+            .collect::<HashMap<usize, BTreeSet<usize>>>();
+        postdominators.insert(EXIT, BTreeSet::from([EXIT]));
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for &address in self.blocks.keys().rev() {
+                let mut set = successors(address).iter()
+                    .map(|successor| postdominators[successor].clone())
+                    .reduce(|a, b| a.intersection(&b).copied().collect())
+                    .unwrap_or_default();
+
+                set.insert(address);
+
+                if set != postdominators[&address] {
+                    postdominators.insert(address, set);
+                    changed = true;
+                }
+            }
+        }
+
+        self.blocks.keys()
+            .map(|&address| {
+                let strict = postdominators[&address].iter()
+                    .copied()
+                    .filter(|&other| other != address)
+                    .collect::<BTreeSet<usize>>();
+
+                // blocks which never reach an exit are post-dominated by
+                // everything, which says nothing useful
+                let immediate = match strict.contains(&EXIT) {
+                    true => strict.iter()
+                        .copied()
+                        .find(|candidate| {
+                            let mut rest = postdominators[candidate].clone();
+                            rest.insert(*candidate);
+                            rest == strict
+                        })
+                        .filter(|&candidate| candidate != EXIT),
+                    false => None,
+                };
+
+                (address, immediate)
+            })
+            .collect()
+    }
+
+    // natural loops keyed by header, merging loops which share a header
+    fn loops(&self) -> BTreeMap<usize, BTreeSet<usize>> {
+        let dominators = self.dominators();
+        let predecessors = self.predecessors();
+        let mut loops = BTreeMap::<usize, BTreeSet<usize>>::new();
+
+        for block in self.blocks.values() {
+            for header in block.terminator.successors() {
+                if !dominators[&block.address].contains(&header) {
+                    continue;
+                }
+
+                let body = loops.entry(header).or_insert_with(|| BTreeSet::from([header]));
+                let mut stack = vec![block.address];
+
+                while let Some(address) = stack.pop() {
+                    if body.insert(address) {
+                        stack.extend(predecessors.get(&address).into_iter().flatten());
+                    }
+                }
+            }
+        }
+
+        loops
+    }
+
+    fn liveness(&self) -> HashMap<usize, HashSet<usize>> {
+        let everything = self.blocks.values()
+            .flat_map(|block| block.instructions.iter())
+            .flat_map(|instruction| reads_writes(instruction).0)
+            .collect::<HashSet<usize>>();
+
+        let mut live_in = self.blocks.keys()
+            .map(|&address| (address, HashSet::new()))
+            .collect::<HashMap<usize, HashSet<usize>>>();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for block in self.blocks.values().rev() {
+                let mut live = match &block.terminator {
+                    Terminator::INDIRECT { .. } => everything.clone(),
+                    terminator => terminator.successors().iter()
+                        .flat_map(|successor| live_in[successor].iter().copied())
+                        .collect(),
+                };
+
+                if let Terminator::BRANCH { condition, .. }
+                    | Terminator::INDIRECT { condition: Some(condition), .. }
+                    = &block.terminator
+                {
+                    if let Operand::MEM(address) = condition.operand {
+                        live.insert(address);
+                    }
+                }
+
+                for instruction in block.instructions.iter().rev() {
+                    let (reads, write) = reads_writes(instruction);
+
+                    if let Some(write) = write {
+                        live.remove(&write);
+                    }
+
+                    live.extend(reads);
+                }
+
+                if live != live_in[&block.address] {
+                    live_in.insert(block.address, live);
+                    changed = true;
+                }
+            }
+        }
+
+        live_in
+    }
+
+    // folds a comparison into the branch which tests its result, dropping
+    // the comparison entirely when nothing else reads the result
+    fn fold_comparisons(&mut self) {
+        let live_in = self.liveness();
+
+        for block in self.blocks.values_mut() {
+            let successors = block.terminator.successors();
+
+            let condition = match &mut block.terminator {
+                Terminator::BRANCH { condition, .. } => condition,
+                Terminator::INDIRECT { condition: Some(condition), .. } => condition,
+                _ => continue,
+            };
+
+            let Operand::MEM(cell) = condition.operand else {
+                continue;
+            };
+
+            let Some(last) = block.instructions.last() else {
+                continue;
+            };
+
+            let comparison = match last.opcode {
+                Some(OpCode::LT) => Comparison::LT,
+                Some(OpCode::EQ) => Comparison::EQ,
+                _ => continue,
+            };
+
+            if last.operands[2] != Operand::MEM(cell) {
+                continue;
+            }
+
+            condition.comparison = Some((comparison, last.operands[0], last.operands[1]));
+
+            let dead = !matches!(block.terminator, Terminator::INDIRECT { .. })
+                && successors.iter().all(|successor| !live_in[successor].contains(&cell));
+
+            if dead {
+                block.instructions.pop();
+            }
+        }
+    }
+
+    fn operand(&self, operand: Operand) -> String {
+        match operand {
+            Operand::IMM(value) => value.to_string(),
+            Operand::MEM(address) if self.code.contains(&address) => {
+                format!("code[{}]", address)
+            },
+            Operand::MEM(address) => format!("v{}", address),
+            Operand::PTR(parameter) => {
+                format!("memory[{}]", self.operand(Operand::MEM(parameter)))
+            },
+        }
+    }
+
+    fn condition(&self, condition: &Condition, negate: bool) -> String {
+        let negated = condition.negated != negate;
+
+        match condition.comparison {
+            Some((comparison, lhs, rhs)) => {
+                let operator = match (comparison, negated) {
+                    (Comparison::LT, false) => "<",
+                    (Comparison::LT, true) => ">=",
+                    (Comparison::EQ, false) => "==",
+                    (Comparison::EQ, true) => "!=",
+                };
+
+                format!("{} {} {}", self.operand(lhs), operator, self.operand(rhs))
+            },
+            None => match negated {
+                false => format!("{} != 0", self.operand(condition.operand)),
+                true => format!("{} == 0", self.operand(condition.operand)),
+            },
+        }
+    }
+
+    fn statement(&self, instruction: &Decoded) -> String {
+        let operands = &instruction.operands;
+
+        let arithmetic = |operator: &str| {
+            let destination = self.operand(operands[2]);
+
+            if let [Operand::IMM(lhs), Operand::IMM(rhs), _] = operands[..] {
+                let value = match operator {
+                    "+" => lhs as i64 + rhs as i64,
+                    _ => lhs as i64 * rhs as i64,
+                };
+                return format!("{} = {};", destination, value);
+            }
+
+            let lhs = self.operand(operands[0]);
+
+            if let ("+", Operand::IMM(value)) = (operator, operands[0]) {
+                if value < 0 && operands[1] == operands[2] {
+                    return format!("{} -= {};", destination, -(value as i64));
+                }
+            }
+
+            let rhs = match (operator, operands[1]) {
+                ("+", Operand::IMM(value)) if value < 0 => {
+                    return match operands[0] == operands[2] {
+                        true => format!("{} -= {};", destination, -(value as i64)),
+                        false => format!("{} = {} - {};", destination, lhs, -(value as i64)),
+                    };
+                },
+                (_, rhs) => self.operand(rhs),
+            };
+
+            if operands[0] == operands[2] {
+                format!("{} {}= {};", destination, operator, rhs)
+            } else if operands[1] == operands[2] {
+                format!("{} {}= {};", destination, operator, lhs)
+            } else {
+                format!("{} = {} {} {};", destination, lhs, operator, rhs)
+            }
+        };
+
+        let comparison = |operator: &str| {
+            format!(
+                "{} = {} {} {};",
+                self.operand(operands[2]),
+                self.operand(operands[0]),
+                operator,
+                self.operand(operands[1]),
+            )
+        };
+
+        let mut statement = match instruction.opcode {
+            Some(OpCode::ADD) => arithmetic("+"),
+            Some(OpCode::MUL) => arithmetic("*"),
+            Some(OpCode::LT) => comparison("<"),
+            Some(OpCode::EQ) => comparison("=="),
+            Some(OpCode::INPUT) => format!("{} = input();", self.operand(operands[0])),
+            Some(OpCode::OUTPUT) => format!("output({});", self.operand(operands[0])),
+            _ => format!(
+                "{}({});",
+                instruction.mnemonic.to_lowercase(),
+                operands.iter()
+                    .map(|operand| self.operand(*operand))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ),
+        };
+
+        if instruction.data {
+            let note = match self.rewritten.contains(&instruction.address) {
+                true => "rewritten at runtime",
+                false => "not an instruction",
+            };
+            write!(statement, " // {}", note).unwrap();
+        }
+
+        let (_, write) = reads_writes(instruction);
+
+        if let Some(address) = write.filter(|address| self.code.contains(address)) {
+            write!(statement, " // self-modifying: rewrites code[{}]", address).unwrap();
+        }
+
+        statement
+    }
+
+    // the statement for a terminator which ends the program
+    fn stop(&self, terminator: &Terminator) -> Option<String> {
+        match terminator {
+            Terminator::HALT => Some("halt();".to_string()),
+            Terminator::OUTSIDE(address) => {
+                Some(format!("trap({}); // outside of memory", address))
+            },
+            _ => None,
+        }
+    }
+}
+
+enum Line {
+    LABEL(usize),
+    CODE(usize, String),
+}
+
+struct LoopContext {
+    header: usize,
+    follow: Option<usize>,
+}
+
+struct Structurer<'a> {
+    program: &'a Program,
+    ipdoms: HashMap<usize, Option<usize>>,
+    loops: BTreeMap<usize, BTreeSet<usize>>,
+    emitted: HashSet<usize>,
+    gotos: HashSet<usize>,
+    lines: Vec<Line>,
+}
+
+impl<'a> Structurer<'a> {
+    fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            ipdoms: program.immediate_postdominators(),
+            loops: program.loops(),
+            emitted: HashSet::new(),
+            gotos: HashSet::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    fn line(&mut self, indent: usize, text: String) {
+        self.lines.push(Line::CODE(indent, text));
+    }
+
+    fn goto(&mut self, indent: usize, target: usize) {
+        self.gotos.insert(target);
+        self.line(indent, format!("goto L{};", target));
+    }
+
+    fn loop_follow(&self, header: usize) -> Option<usize> {
+        let body = &self.loops[&header];
+
+        let exits = body.iter()
+            .flat_map(|address| self.program.blocks[address].terminator.successors())
+            .filter(|successor| !body.contains(successor))
+            .collect::<BTreeSet<usize>>();
+
+        match self.ipdoms[&header] {
+            Some(follow) if exits.contains(&follow) => Some(follow),
+            _ => exits.first().copied(),
+        }
+    }
+
+    // emits a jump to a block which cannot be structured as a fallthrough,
+    // returning true if one was needed
+    fn jump(&mut self, indent: usize, target: usize, contexts: &[LoopContext]) -> bool {
+        if let Some(context) = contexts.last() {
+            if target == context.header {
+                self.line(indent, "continue;".to_string());
+                return true;
+            }
+
+            if Some(target) == context.follow {
+                self.line(indent, "break;".to_string());
+                return true;
+            }
+        }
+
+        // blocks which only end the program are repeated rather than
+        // jumped to, so that guards need not jump into one another
+        let block = &self.program.blocks[&target];
+
+        if block.instructions.is_empty() {
+            if let Some(text) = self.program.stop(&block.terminator) {
+                self.line(indent, text);
+                return true;
+            }
+        }
+
+        if self.emitted.contains(&target) {
+            self.goto(indent, target);
+            return true;
+        }
+
+        let outer = contexts.iter()
+            .any(|context| context.header == target || context.follow == Some(target));
+
+        if outer {
+            self.goto(indent, target);
+            return true;
+        }
+
+        false
+    }
+
+    fn region(
+        &mut self,
+        mut current: Option<usize>,
+        stop: Option<usize>,
+        contexts: &mut Vec<LoopContext>,
+        indent: usize
+    ) {
+        while let Some(address) = current {
+            if Some(address) == stop {
+                return;
+            }
+
+            if self.jump(indent, address, contexts) {
+                return;
+            }
+
+            if self.loops.contains_key(&address) {
+                let follow = self.loop_follow(address);
+
+                contexts.push(LoopContext { header: address, follow });
+                self.lines.push(Line::LABEL(address));
+                let header_line = self.lines.len();
+                self.line(indent, "while (true) {".to_string());
+
+                let next = self.block(address, contexts, indent + 1);
+                self.region(next, None, contexts, indent + 1);
+
+                // falling off the end of the body restarts the loop anyway
+                if let Some(Line::CODE(_, last)) = self.lines.last() {
+                    if last == "continue;" {
+                        self.lines.pop();
+                    }
+                }
+
+                self.line(indent, "}".to_string());
+                contexts.pop();
+
+                self.simplify_loop(header_line, indent);
+
+                current = follow;
+                continue;
+            }
+
+            self.lines.push(Line::LABEL(address));
+            current = self.block(address, contexts, indent);
+        }
+    }
+
+    // rewrites 'while (true) { if (c) break; ...' as 'while (!c) { ...'
+    fn simplify_loop(&mut self, header_line: usize, indent: usize) {
+        let Some(Line::CODE(_, first)) = self.lines.get(header_line + 1) else {
+            return;
+        };
+
+        let Some(condition) = first.strip_prefix("if (")
+            .and_then(|rest| rest.strip_suffix(") break;"))
+        else {
+            return;
+        };
+
+        let condition = negate(condition);
+
+        self.lines[header_line] = Line::CODE(indent, format!("while ({}) {{", condition));
+        self.lines.remove(header_line + 1);
+    }
+
+    // emits the statements of a single block, returning the block which
+    // follows it in the current region
+    fn block(
+        &mut self,
+        address: usize,
+        contexts: &mut Vec<LoopContext>,
+        indent: usize
+    ) -> Option<usize> {
+        self.emitted.insert(address);
+
+        let program = self.program;
+        let block = &program.blocks[&address];
+
+        for instruction in block.instructions.iter() {
+            let statement = program.statement(instruction);
+            self.line(indent, statement);
+        }
+
+        match &block.terminator {
+            Terminator::FALL(next) | Terminator::GOTO(next) => Some(*next),
+            Terminator::HALT | Terminator::OUTSIDE(_) => {
+                let text = program.stop(&block.terminator).unwrap();
+                self.line(indent, text);
+                None
+            },
+            Terminator::INDIRECT { condition, target, fallthrough } => {
+                let target = program.operand(*target);
+
+                let text = match condition {
+                    Some(condition) => format!(
+                        "if ({}) goto *{};",
+                        program.condition(condition, false),
+                        target,
+                    ),
+                    None => format!("goto *{};", target),
+                };
+
+                self.line(indent, text);
+
+                *fallthrough
+            },
+            Terminator::BRANCH { condition, taken, fallthrough } => {
+                let (taken, fallthrough) = (*taken, *fallthrough);
+                let follow = self.ipdoms[&address];
+
+                // branches which leave or restart the enclosing loop
+                for (target, other, negate) in [
+                    (taken, fallthrough, false),
+                    (fallthrough, taken, true),
+                ] {
+                    let exits = contexts.last().is_some_and(|context| {
+                        target == context.header || Some(target) == context.follow
+                    });
+
+                    if exits {
+                        let text = format!("if ({}) ", program.condition(condition, negate));
+                        let start = self.lines.len();
+                        self.jump(indent, target, contexts);
+
+                        if let Some(Line::CODE(_, jump)) = self.lines.get_mut(start) {
+                            *jump = format!("{}{}", text, jump);
+                        }
+
+                        return Some(other);
+                    }
+                }
+
+                // whether a block ends the program, perhaps after jumping
+                // through blocks which do nothing else
+                let terminal = |mut address: usize| {
+                    for _ in 0..program.blocks.len() {
+                        let block = &program.blocks[&address];
+
+                        match (&block.terminator, block.instructions.is_empty()) {
+                            (terminator, _) if terminator.successors().is_empty() => {
+                                return true;
+                            },
+                            (Terminator::GOTO(next) | Terminator::FALL(next), true) => {
+                                address = *next;
+                            },
+                            _ => return false,
+                        }
+                    }
+
+                    false
+                };
+
+                // without a join point, a side which ends the program is
+                // written as a guard so the other side need not be nested
+                let follow = match follow {
+                    None if terminal(taken) => {
+                        self.line(indent, format!("if ({}) {{", program.condition(condition, false)));
+                        self.region(Some(taken), None, contexts, indent + 1);
+                        self.line(indent, "}".to_string());
+                        return Some(fallthrough);
+                    },
+                    None if terminal(fallthrough) => {
+                        self.line(indent, format!("if ({}) {{", program.condition(condition, true)));
+                        self.region(Some(fallthrough), None, contexts, indent + 1);
+                        self.line(indent, "}".to_string());
+                        return Some(taken);
+                    },
+                    follow => follow,
+                };
+
+                let (condition, then, otherwise) = match follow {
+                    Some(follow) if follow == taken => {
+                        (program.condition(condition, true), fallthrough, None)
+                    },
+                    Some(follow) if follow == fallthrough => {
+                        (program.condition(condition, false), taken, None)
+                    },
+                    _ => (program.condition(condition, false), taken, Some(fallthrough)),
+                };
+
+                self.line(indent, format!("if ({}) {{", condition));
+                self.region(Some(then), follow, contexts, indent + 1);
+
+                if let Some(otherwise) = otherwise {
+                    self.line(indent, "} else {".to_string());
+                    self.region(Some(otherwise), follow, contexts, indent + 1);
+                }
+
+                self.line(indent, "}".to_string());
+
+                follow
+            },
+        }
+    }
+}
+
+fn negate(condition: &str) -> String {
+    for (operator, negated) in [
+        (" >= ", " < "),
+        (" != ", " == "),
+        (" == ", " != "),
+        (" < ", " >= "),
+    ] {
+        if condition.contains(operator) {
+            return condition.replacen(operator, negated, 1);
+        }
+    }
+
+    format!("!({})", condition)
+}
+
+pub fn decompile(memory: &[i32], instruction_set: &InstructionSet) -> String {
+    let mut program = discover(memory, instruction_set);
+    program.fold_comparisons();
+
+    let mut structurer = Structurer::new(&program);
+    let mut contexts = Vec::new();
+
+    structurer.region(Some(0), None, &mut contexts, 1);
+
+    // blocks only reachable through gotos which were never emitted inline
+    while let Some(address) = program.blocks.keys()
+        .copied()
+        .find(|address| {
+            structurer.gotos.contains(address) && !structurer.emitted.contains(address)
+        })
+    {
+        structurer.region(Some(address), None, &mut contexts, 1);
+    }
+
+    let mut text = String::new();
+
+    writeln!(
+        text,
+        "// decompiled from {} words, {} basic blocks",
+        memory.len(),
+        program.blocks.len(),
+    ).unwrap();
+
+    let mut variables = BTreeSet::new();
+
+    for block in program.blocks.values() {
+        for instruction in block.instructions.iter() {
+            let (reads, write) = reads_writes(instruction);
+            variables.extend(reads.into_iter().chain(write));
+        }
+
+        let (condition, target) = match &block.terminator {
+            Terminator::BRANCH { condition, .. } => (Some(condition), None),
+            Terminator::INDIRECT { condition, target, .. } => {
+                (condition.as_ref(), Some(*target))
+            },
+            _ => (None, None),
+        };
+
+        let operands = condition.into_iter()
+            .flat_map(|condition| {
+                match condition.comparison {
+                    Some((_, lhs, rhs)) => [Some(lhs), Some(rhs)],
+                    None => [Some(condition.operand), None],
+                }
+            })
+            .chain([target])
+            .flatten();
+
+        for operand in operands {
+            if let Operand::MEM(address) = operand {
+                variables.insert(address);
+            }
+        }
+    }
+
+    for address in variables.iter().filter(|address| !program.code.contains(address)) {
+        let value = memory.get(*address).copied().unwrap_or(0);
+        writeln!(text, "int v{} = {};", address, value).unwrap();
+    }
+
+    writeln!(text).unwrap();
+    writeln!(text, "void main() {{").unwrap();
+
+    for line in structurer.lines.iter() {
+        match line {
+            Line::LABEL(address) if structurer.gotos.contains(address) => {
+                writeln!(text, "L{}:", address).unwrap();
+            },
+            Line::LABEL(_) => (),
+            Line::CODE(indent, code) => {
+                writeln!(text, "{}{}", "    ".repeat(*indent), code).unwrap();
+            },
+        }
+    }
+
+    writeln!(text, "}}").unwrap();
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{compiler, program};
+
+    fn path(relative: &str) -> String {
+        format!("{}/{}", env!("CARGO_MANIFEST_DIR"), relative)
+    }
+
+    // decompiles the program and checks the result against the expected
+    // output checked in under golden/decompile, returning it
+    fn check(memory: &[i32], golden: &str) -> String {
+        let expected = std::fs::read_to_string(path(&format!("golden/decompile/{}", golden)))
+            .unwrap();
+
+        let text = decompile(memory, &InstructionSet::default());
+        assert_eq!(text, expected);
+        text
+    }
+
+    // decompiles a puzzle input after applying the given edits
+    fn check_input(input: &str, edits: &[(usize, i32)], golden: &str) -> String {
+        let mut memory = program::load(path(&format!("input/{}", input))).unwrap();
+
+        for &(address, value) in edits {
+            memory[address] = value;
+        }
+
+        check(&memory, golden)
+    }
+
+    // decompiles the compiled form of a source file next to the golden one,
+    // so that its branches depend on input rather than on stored values
+    fn check_source(name: &str) -> String {
+        let source = std::fs::read_to_string(path(&format!("golden/decompile/{}.src", name)))
+            .unwrap();
+
+        check(&compiler::compile(&source).unwrap(), &format!("{}.txt", name))
+    }
+
+    // the indentation of the line containing the text
+    fn indent(text: &str, line: &str) -> usize {
+        let line = text.lines().find(|candidate| candidate.contains(line)).unwrap();
+        line.len() - line.trim_start().len()
+    }
+
+    // the first instruction rewrites the third with the input, choosing
+    // between the two parts, so as stored only the part 1 tests decode
+    #[test]
+    fn day05() {
+        check_input("day05/input.txt", &[], "day05.txt");
+    }
+
+    // jumping straight to the part 2 tests, which choose where to jump
+    // next by storing addresses in code[0]
+    #[test]
+    fn day05_part2() {
+        let edits = [(2, 1105), (3, 1), (4, 238)];
+        check_input("day05/input.txt", &edits, "day05-part2.txt");
+    }
+
+    // an if/else if/else chain, then conditions joined with && and ||, all
+    // on values read from input
+    #[test]
+    fn branches() {
+        let text = check_source("branches");
+
+        assert!(text.contains("if (v109 >= 5) {"));
+        assert_eq!(indent(&text, "if (v109 != 7) {"), 8);
+        assert_eq!(text.matches("} else {").count(), 2);
+        assert!(!text.contains("goto"));
+    }
+
+    // nested loops bounded by input, with the comparison each one tests
+    // folded into its condition
+    #[test]
+    fn loops() {
+        let text = check_source("loops");
+
+        assert_eq!(indent(&text, "while (v67 < v65) {"), 4);
+        assert_eq!(indent(&text, "while (v68 < v67) {"), 8);
+        assert!(!text.contains(" = v67 < v65;"));
+        assert!(!text.contains("goto"));
+    }
+}
@@ -0,0 +1,66 @@
+use super::{Instruction, InstructionSet, ParameterMode};
+
+// a single decoded instruction, or a data word where memory does not hold a
+// valid instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub address: usize,
+    pub size: usize,
+    pub text: String,
+}
+
+pub fn decode(
+    memory: &[i32],
+    address: usize,
+    instruction_set: &InstructionSet
+) -> Line {
+    let data = Line {
+        address,
+        size: 1,
+        text: format!("DATA {}", memory[address]),
+    };
+
+    let instruction = Instruction::decode(memory[address]);
+
+    let Some(operation) = instruction_set.get(instruction.opcode_id) else {
+        return data;
+    };
+
+    if address + operation.arity() >= memory.len() {
+        return data;
+    }
+
+    let mut params = Vec::new();
+
+    for offset in 1..=operation.arity() {
+        let value = memory[address + offset];
+
+        match instruction.pmode(offset) {
+            Ok(ParameterMode::POSITION) => params.push(format!("[{}]", value)),
+            Ok(ParameterMode::IMMEDIATE) => params.push(format!("{}", value)),
+            Err(_) => return data,
+        }
+    }
+
+    let text = match params.is_empty() {
+        true => operation.mnemonic().to_string(),
+        false => format!("{} {}", operation.mnemonic(), params.join(", ")),
+    };
+
+    Line { address, size: operation.size(), text }
+}
+
+// decodes memory linearly from address zero; data interleaved with code can
+// throw the listing out of step until it happens to realign
+pub fn disassemble(memory: &[i32], instruction_set: &InstructionSet) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut address = 0;
+
+    while address < memory.len() {
+        let line = decode(memory, address, instruction_set);
+        address += line.size;
+        lines.push(line);
+    }
+
+    lines
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+use super::Overflow;
+
+// an error raised by the program being executed, as opposed to a bug in the
+// interpreter; the instruction pointer is left on the faulting instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fault {
+    OPCODE { address: usize, id: i32 },
+    PMODE { address: usize, id: i32 },
+    // the instruction at address, or one of its parameters, refers to memory
+    // which does not exist
+    ADDRESS { address: usize, target: i64 },
+    OVERFLOW(Overflow),
+}
+
+impl Fault {
+    pub fn address(&self) -> usize {
+        match self {
+            Self::OPCODE { address, .. } => *address,
+            Self::PMODE { address, .. } => *address,
+            Self::ADDRESS { address, .. } => *address,
+            Self::OVERFLOW(overflow) => overflow.address,
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OPCODE { address, id } => write!(
+                f,
+                "unrecognized opcode id {} at address {}",
+                id, address,
+            ),
+            Self::PMODE { address, id } => write!(
+                f,
+                "unrecognized parameter mode id {} at address {}",
+                id, address,
+            ),
+            Self::ADDRESS { address, target } => write!(
+                f,
+                "memory address {} out of range at address {}",
+                target, address,
+            ),
+            Self::OVERFLOW(overflow) => write!(f, "{}", overflow),
+        }
+    }
+}
+
+impl std::error::Error for Fault {}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use super::InstructionSet;
+
+// the gas charged for each instruction, keyed by opcode id so that custom
+// instructions can be priced too
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostModel {
+    costs: HashMap<i32, u64>,
+    default: u64,
+}
+
+impl Default for CostModel {
+    // every instruction costs one, so gas used equals steps
+    fn default() -> Self {
+        Self::uniform(1)
+    }
+}
+
+impl CostModel {
+    pub fn uniform(cost: u64) -> Self {
+        Self { costs: HashMap::new(), default: cost }
+    }
+
+    pub fn set(&mut self, opcode_id: i32, cost: u64) -> &mut Self {
+        self.costs.insert(opcode_id, cost);
+        self
+    }
+
+    pub fn cost(&self, opcode_id: i32) -> u64 {
+        self.costs.get(&opcode_id).copied().unwrap_or(self.default)
+    }
+
+    // parses 'MNEMONIC=cost', looking the mnemonic up in the instruction set
+    pub fn set_mnemonic(
+        &mut self,
+        spec: &str,
+        instruction_set: &InstructionSet
+    ) -> Result<&mut Self, String> {
+        let (mnemonic, cost) = spec.split_once('=')
+            .ok_or_else(|| format!("expected MNEMONIC=cost: {}", spec))?;
+
+        let mnemonic = mnemonic.trim();
+
+        let cost = cost.trim().parse::<u64>()
+            .map_err(|_| format!("invalid cost: {}", cost))?;
+
+        let id = instruction_set.ids()
+            .into_iter()
+            .find(|&id| match instruction_set.get(id) {
+                Some(operation) => operation.mnemonic().eq_ignore_ascii_case(mnemonic),
+                None => false,
+            })
+            .ok_or_else(|| format!("unknown instruction: {}", mnemonic))?;
+
+        Ok(self.set(id, cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{Computer, Signal};
+
+    // adds twice, then halts
+    const PROGRAM: [i32; 9] = [1101, 1, 1, 0, 1101, 1, 1, 0, 99];
+
+    #[test]
+    fn stops_before_exceeding_limit() {
+        let mut computer = Computer::new(&PROGRAM);
+        computer.with_cost_model(CostModel::uniform(2)).with_gas_limit(Some(3));
+
+        assert!(matches!(computer.run(), Ok(Signal::GAS)));
+        assert_eq!((computer.steps(), computer.gas_used()), (1, 2));
+    }
+
+    #[test]
+    fn total_which_overflows_runs_out_of_gas() {
+        let mut computer = Computer::new(&PROGRAM);
+        computer.with_cost_model(CostModel::uniform(u64::MAX));
+
+        assert!(matches!(computer.run(), Ok(Signal::GAS)));
+        assert_eq!((computer.steps(), computer.gas_used()), (1, u64::MAX));
+    }
+}
@@ -0,0 +1,239 @@
+// a localhost TCP gateway into a simulated network
+//
+// clients send lines of the form 'send <destination> <x> <y>' to inject
+// packets, and receive every line of network traffic as it happens, e.g.
+// 'packet 3 255 17 42', 'idle' or 'nat 17 42'
+//
+// each client has its own writer thread, so a client which stops reading
+// never holds up the network; one which falls too far behind is disconnected
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::network::{Packet, Traffic};
+
+// the most lines which may be waiting to be written to one client
+pub const CLIENT_BACKLOG: usize = 1024;
+
+pub struct Gateway {
+    address: SocketAddr,
+    clients: Arc<Mutex<Vec<Client>>>,
+    injected: Receiver<Packet>,
+}
+
+struct Client {
+    stream: TcpStream,
+    // lines waiting for the client's writer thread
+    lines: SyncSender<String>,
+}
+
+fn parse_command(line: &str) -> Result<Packet, String> {
+    let parts = line.split_whitespace().collect::<Vec<&str>>();
+
+    let ["send", destination, x, y] = parts[..] else {
+        return Err(format!("expected 'send <destination> <x> <y>': {}", line));
+    };
+
+    let parse = |value: &str| {
+        value.parse::<i32>().map_err(|_| format!("invalid number: {}", value))
+    };
+
+    Ok(Packet {
+        source: None,
+        destination: parse(destination)?,
+        x: parse(x)?,
+        y: parse(y)?,
+    })
+}
+
+fn write_client(mut stream: TcpStream, lines: Receiver<String>) {
+    for line in lines {
+        if stream.write_all(line.as_bytes()).is_err() {
+            break;
+        }
+    }
+
+    // also ends the reader, whether the client went away or was dropped
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+fn serve_client(
+    stream: TcpStream,
+    writer: SyncSender<String>,
+    injected: Sender<Packet>,
+) -> io::Result<()> {
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_command(&line) {
+            Ok(packet) => {
+                if injected.send(packet).is_err() {
+                    break;
+                }
+            },
+            Err(message) => {
+                if writer.send(format!("error {}\n", message)).is_err() {
+                    break;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+impl Gateway {
+    // listens on 127.0.0.1 only; port 0 picks a free port
+    pub fn bind(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let address = listener.local_addr()?;
+
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let (sender, injected) = mpsc::channel();
+
+        let accepted = Arc::clone(&clients);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+
+                let clones = (stream.try_clone(), stream.try_clone());
+
+                let (Ok(writer), Ok(client)) = clones else {
+                    continue;
+                };
+
+                let (lines, pending) = mpsc::sync_channel(CLIENT_BACKLOG);
+                thread::spawn(move || write_client(writer, pending));
+
+                let client = Client { stream: client, lines: lines.clone() };
+                accepted.lock().unwrap().push(client);
+
+                let sender = sender.clone();
+                thread::spawn(move || serve_client(stream, lines, sender));
+            }
+        });
+
+        Ok(Self { address, clients, injected })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    // packets sent by clients since the last poll
+    pub fn poll(&self) -> Vec<Packet> {
+        self.injected.try_iter().collect()
+    }
+
+    // queues a line of traffic for every client without waiting for it to be
+    // written, forgetting those which have disconnected or fallen behind
+    pub fn broadcast(&self, traffic: &Traffic) {
+        let line = format!("{}\n", traffic);
+
+        self.clients.lock().unwrap().retain(|client| {
+            if client.lines.try_send(line.clone()).is_ok() {
+                return true;
+            }
+
+            // unblocks the client's writer and reader threads
+            let _ = client.stream.shutdown(Shutdown::Both);
+            false
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn connect(gateway: &Gateway) -> (TcpStream, BufReader<TcpStream>) {
+        let stream = TcpStream::connect(gateway.address()).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        (stream, reader)
+    }
+
+    fn read_line(reader: &mut BufReader<TcpStream>) -> String {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        line
+    }
+
+    fn poll_one(gateway: &Gateway) -> Packet {
+        let start = Instant::now();
+
+        loop {
+            if let [packet] = gateway.poll()[..] {
+                return packet;
+            }
+
+            assert!(start.elapsed() < Duration::from_secs(5), "no packet injected");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn clients_inject_packets() {
+        let gateway = Gateway::bind(0).unwrap();
+        let (mut stream, mut reader) = connect(&gateway);
+
+        writeln!(stream, "send 3 -7 42").unwrap();
+
+        let packet = Packet { source: None, destination: 3, x: -7, y: 42 };
+        assert_eq!(poll_one(&gateway), packet);
+
+        writeln!(stream, "send 3 x 42").unwrap();
+        assert_eq!(read_line(&mut reader), "error invalid number: x\n");
+        assert_eq!(gateway.poll(), vec![]);
+    }
+
+    #[test]
+    fn traffic_is_broadcast() {
+        let gateway = Gateway::bind(0).unwrap();
+        let (mut stream, mut reader) = connect(&gateway);
+
+        // once a packet has arrived the client is known to the gateway
+        writeln!(stream, "send 0 1 2").unwrap();
+        let packet = poll_one(&gateway);
+
+        gateway.broadcast(&Traffic::SENT(packet));
+        gateway.broadcast(&Traffic::IDLE);
+
+        assert_eq!(read_line(&mut reader), "packet - 0 1 2\n");
+        assert_eq!(read_line(&mut reader), "idle\n");
+    }
+
+    #[test]
+    fn client_which_stops_reading_is_dropped() {
+        let gateway = Gateway::bind(0).unwrap();
+        let (mut stream, _reader) = connect(&gateway);
+
+        writeln!(stream, "send 0 1 2").unwrap();
+        poll_one(&gateway);
+
+        // far more than the socket buffers hold, and none of it is read
+        let start = Instant::now();
+
+        for _ in 0..CLIENT_BACKLOG * 1024 {
+            gateway.broadcast(&Traffic::IDLE);
+
+            if gateway.clients.lock().unwrap().is_empty() {
+                break;
+            }
+        }
+
+        assert!(gateway.clients.lock().unwrap().is_empty());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
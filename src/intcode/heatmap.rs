@@ -0,0 +1,227 @@
+use std::fmt::Write;
+
+// per-address counts of operand reads, writes and instruction executions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Heatmap {
+    pub reads: Vec<u64>,
+    pub writes: Vec<u64>,
+    pub executions: Vec<u64>,
+}
+
+fn bump(counts: &mut Vec<u64>, address: usize) {
+    if address >= counts.len() {
+        counts.resize(address + 1, 0);
+    }
+
+    counts[address] += 1;
+}
+
+fn count(counts: &[u64], address: usize) -> u64 {
+    counts.get(address).copied().unwrap_or(0)
+}
+
+// maps a count onto 0..=255 on a log scale so that a handful of accesses is
+// still visible next to addresses touched millions of times
+fn intensity(value: u64, max: u64) -> u8 {
+    if value == 0 || max == 0 {
+        return 0;
+    }
+
+    let scaled = (value as f64).ln_1p() / (max as f64).ln_1p();
+    (64.0 + scaled * 191.0).round() as u8
+}
+
+impl Heatmap {
+    // covers at least the given number of addresses, growing as the program
+    // touches memory beyond them
+    pub fn new(size: usize) -> Self {
+        Self {
+            reads: vec![0; size],
+            writes: vec![0; size],
+            executions: vec![0; size],
+        }
+    }
+
+    pub fn read(&mut self, address: usize) {
+        bump(&mut self.reads, address);
+    }
+
+    pub fn write(&mut self, address: usize) {
+        bump(&mut self.writes, address);
+    }
+
+    pub fn execute(&mut self, address: usize) {
+        bump(&mut self.executions, address);
+    }
+
+    pub fn len(&self) -> usize {
+        self.reads.len().max(self.writes.len()).max(self.executions.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // addresses which were executed as code and also written to
+    pub fn self_modified(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&address| {
+                count(&self.executions, address) > 0
+                    && count(&self.writes, address) > 0
+            })
+            .collect()
+    }
+
+    // writes, reads and executions are drawn in the red, green and blue
+    // channels respectively, so self-modifying code shows up as magenta and
+    // lookup tables as green; each channel is scaled by its busiest address
+    fn colours(&self) -> Vec<[u8; 3]> {
+        let max = |counts: &[u64]| counts.iter().copied().max().unwrap_or(0);
+
+        let (writes, reads, executions) =
+            (max(&self.writes), max(&self.reads), max(&self.executions));
+
+        (0..self.len())
+            .map(|address| [
+                intensity(count(&self.writes, address), writes),
+                intensity(count(&self.reads, address), reads),
+                intensity(count(&self.executions, address), executions),
+            ])
+            .collect()
+    }
+
+    fn rows(&self, columns: usize) -> usize {
+        self.len().div_ceil(columns).max(1)
+    }
+
+    // a binary PPM with one scale x scale square per address, laid out in
+    // rows of the given number of columns
+    pub fn render_ppm(&self, columns: usize, scale: usize) -> Vec<u8> {
+        let columns = columns.max(1);
+        let scale = scale.max(1);
+        let (width, height) = (columns * scale, self.rows(columns) * scale);
+
+        let colours = self.colours();
+
+        let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+        for y in 0..height {
+            for x in 0..width {
+                let address = (y / scale) * columns + x / scale;
+                let colour = colours.get(address).copied().unwrap_or([0, 0, 0]);
+                image.extend_from_slice(&colour);
+            }
+        }
+
+        image
+    }
+
+    // an SVG with the same layout as the PPM, where hovering over a cell
+    // shows its address and counts
+    pub fn render_svg(&self, columns: usize, scale: usize) -> String {
+        let columns = columns.max(1);
+        let scale = scale.max(1);
+        let (width, height) = (columns * scale, self.rows(columns) * scale);
+
+        let mut svg = String::new();
+
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+            width, height,
+        ).unwrap();
+
+        writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"black\"/>").unwrap();
+
+        for (address, [r, g, b]) in self.colours().into_iter().enumerate() {
+            if [r, g, b] == [0, 0, 0] {
+                continue;
+            }
+
+            writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:02x}{:02x}{:02x}\">\
+                 <title>{}: {} reads, {} writes, {} executions</title></rect>",
+                (address % columns) * scale,
+                (address / columns) * scale,
+                scale,
+                scale,
+                r, g, b,
+                address,
+                count(&self.reads, address),
+                count(&self.writes, address),
+                count(&self.executions, address),
+            ).unwrap();
+        }
+
+        writeln!(svg, "</svg>").unwrap();
+
+        svg
+    }
+
+    pub fn summary(&self, top: usize) -> String {
+        let total = |counts: &[u64]| counts.iter().sum::<u64>();
+        let touched = |counts: &[u64]| counts.iter().filter(|&&n| n > 0).count();
+
+        let mut text = String::new();
+
+        writeln!(text, "addresses touched: {}", (0..self.len())
+            .filter(|&address| {
+                count(&self.reads, address) > 0
+                    || count(&self.writes, address) > 0
+                    || count(&self.executions, address) > 0
+            })
+            .count()
+        ).unwrap();
+
+        for (name, counts) in [
+            ("reads", &self.reads),
+            ("writes", &self.writes),
+            ("executions", &self.executions),
+        ] {
+            writeln!(
+                text, "{}: {} over {} addresses",
+                name, total(counts), touched(counts),
+            ).unwrap();
+        }
+
+        let self_modified = self.self_modified();
+
+        if !self_modified.is_empty() {
+            let addresses = self_modified.iter()
+                .map(|address| address.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            writeln!(text, "self-modified code: {}", addresses).unwrap();
+        }
+
+        let mut hottest = (0..self.len())
+            .map(|address| {
+                let accesses = count(&self.reads, address)
+                    + count(&self.writes, address)
+                    + count(&self.executions, address);
+                (accesses, address)
+            })
+            .filter(|&(accesses, _)| accesses > 0)
+            .collect::<Vec<(u64, usize)>>();
+
+        hottest.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        if !hottest.is_empty() && top > 0 {
+            writeln!(text, "hottest addresses:").unwrap();
+        }
+
+        for &(_, address) in hottest.iter().take(top) {
+            writeln!(
+                text, "    {:>6}: {} reads, {} writes, {} executions",
+                address,
+                count(&self.reads, address),
+                count(&self.writes, address),
+                count(&self.executions, address),
+            ).unwrap();
+        }
+
+        text
+    }
+}
@@ -0,0 +1,301 @@
+// a simulated packet network of computers running the same program
+//
+// each node is booted with its address as its first input, and sends a
+// packet by outputting the destination address followed by the X and Y
+// values; a node asking for input receives the X and Y of each queued
+// packet in turn, or -1 when its queue is empty
+//
+// packets sent to NAT_ADDRESS go to the NAT, which remembers only the last
+// one and sends it to node 0 whenever the whole network goes idle
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use super::{Computer, Fault, Signal};
+
+pub const NAT_ADDRESS: i32 = 255;
+
+// the most instructions a node may execute in one turn, so that a node which
+// never asks for input cannot stall the others
+pub const TURN_STEPS: u64 = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Packet {
+    // None for packets from the NAT or injected from outside the network
+    pub source: Option<usize>,
+    pub destination: i32,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.source {
+            Some(source) => write!(f, "{}", source)?,
+            None => write!(f, "-")?,
+        }
+
+        write!(f, " {} {} {}", self.destination, self.x, self.y)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Traffic {
+    SENT(Packet),
+    // the destination is neither a node nor the NAT
+    DROPPED(Packet),
+    // every queue is empty and no node sent anything in the last round
+    IDLE,
+    // the NAT sent its last packet to node 0
+    WAKE(Packet),
+    HALTED(usize),
+    FAULT(usize, Fault),
+}
+
+impl fmt::Display for Traffic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SENT(packet) => write!(f, "packet {}", packet),
+            Self::DROPPED(packet) => write!(f, "dropped {}", packet),
+            Self::IDLE => write!(f, "idle"),
+            Self::WAKE(packet) => write!(f, "nat {} {}", packet.x, packet.y),
+            Self::HALTED(node) => write!(f, "halted {}", node),
+            Self::FAULT(node, fault) => write!(f, "fault {} {}", node, fault),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Nat {
+    pub last: Option<Packet>,
+    // every packet sent to wake the network, oldest first
+    pub woken: Vec<Packet>,
+}
+
+pub struct Network {
+    nodes: Vec<Computer>,
+    queues: Vec<VecDeque<Packet>>,
+    running: Vec<bool>,
+    nat: Nat,
+    idle: bool,
+}
+
+impl Network {
+    pub fn new(program: &[i32], size: usize) -> Self {
+        let nodes = (0..size)
+            .map(|address| {
+                let mut computer = Computer::new(program);
+                computer.input_buffer.push_back(address as i32);
+                computer
+            })
+            .collect();
+
+        Self {
+            nodes,
+            queues: vec![VecDeque::new(); size],
+            running: vec![true; size],
+            nat: Nat::default(),
+            idle: false,
+        }
+    }
+
+    pub fn nodes(&self) -> &[Computer] {
+        &self.nodes
+    }
+
+    pub fn nat(&self) -> &Nat {
+        &self.nat
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.running.iter().all(|running| !running)
+    }
+
+    // delivers a packet as though a node had sent it
+    pub fn inject(&mut self, packet: Packet) -> Traffic {
+        self.idle = false;
+        self.route(packet)
+    }
+
+    fn route(&mut self, packet: Packet) -> Traffic {
+        if packet.destination == NAT_ADDRESS {
+            self.nat.last = Some(packet);
+            return Traffic::SENT(packet);
+        }
+
+        match usize::try_from(packet.destination) {
+            Ok(destination) if destination < self.nodes.len() => {
+                self.queues[destination].push_back(packet);
+                Traffic::SENT(packet)
+            },
+            _ => Traffic::DROPPED(packet),
+        }
+    }
+
+    // gives every running node one turn, then wakes the network through the
+    // NAT if nothing happened
+    pub fn round(&mut self) -> Vec<Traffic> {
+        let mut traffic = Vec::new();
+        let mut quiet = true;
+
+        for node in 0..self.nodes.len() {
+            if !self.running[node] {
+                continue;
+            }
+
+            let computer = &mut self.nodes[node];
+
+            // a -1 left unread from an earlier turn still tells the node its
+            // queue is empty, so another would only pile up behind it
+            if !self.queues[node].is_empty() {
+                quiet = false;
+            } else if computer.input_buffer.is_empty() {
+                computer.input_buffer.push_back(-1);
+            }
+
+            for packet in self.queues[node].drain(..) {
+                computer.input_buffer.push_back(packet.x);
+                computer.input_buffer.push_back(packet.y);
+            }
+
+            computer.with_step_limit(Some(computer.steps() + TURN_STEPS));
+
+            let result = computer.run();
+
+            let mut sent = Vec::new();
+
+            while computer.output_buffer.len() >= 3 {
+                let mut values = computer.output_buffer.drain(..3);
+
+                sent.push(Packet {
+                    source: Some(node),
+                    destination: values.next().unwrap(),
+                    x: values.next().unwrap(),
+                    y: values.next().unwrap(),
+                });
+            }
+
+            match result {
+                Ok(Signal::INPUT) => (),
+                Ok(Signal::HALT) => {
+                    self.running[node] = false;
+                    traffic.push(Traffic::HALTED(node));
+                },
+                Ok(_) => quiet = false,
+                Err(fault) => {
+                    self.running[node] = false;
+                    traffic.push(Traffic::FAULT(node, fault));
+                },
+            }
+
+            for packet in sent {
+                quiet = false;
+                traffic.push(self.route(packet));
+            }
+        }
+
+        if !quiet {
+            self.idle = false;
+            return traffic;
+        }
+
+        if self.nodes.is_empty() {
+            return traffic;
+        }
+
+        if let Some(packet) = self.nat.last {
+            let packet = Packet { source: None, destination: 0, ..packet };
+
+            self.nat.woken.push(packet);
+            self.queues[0].push_back(packet);
+            self.idle = false;
+            traffic.push(Traffic::WAKE(packet));
+        } else if !self.idle {
+            self.idle = true;
+            traffic.push(Traffic::IDLE);
+        }
+
+        traffic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // reads its address, sends (address, address) to address + 1, then
+    // keeps reading
+    const FORWARD: [i32; 20] = [
+        3, 17, 1001, 17, 1, 18, 4, 18, 4, 17, 4, 17, 3, 19, 1105, 1, 12, 0, 0, 0,
+    ];
+
+    // reads its address, sends 7 42 to the NAT, then keeps reading
+    const NAT: [i32; 15] = [3, 13, 104, 255, 104, 7, 104, 42, 3, 14, 1105, 1, 8, 0, 0];
+
+    // reads its address, then never asks for input again
+    const SPIN: [i32; 6] = [3, 5, 1105, 1, 2, 0];
+
+    #[test]
+    fn packets_are_routed_or_dropped() {
+        let mut network = Network::new(&FORWARD, 2);
+
+        let sent = Packet { source: Some(0), destination: 1, x: 0, y: 0 };
+        let dropped = Packet { source: Some(1), destination: 2, x: 1, y: 1 };
+
+        assert_eq!(network.round(), vec![Traffic::SENT(sent), Traffic::DROPPED(dropped)]);
+
+        // node 1 took its turn after node 0 and read the packet then
+        assert_eq!(network.round(), vec![Traffic::IDLE]);
+        assert!(network.is_idle());
+        assert!(network.nodes()[1].input_buffer.is_empty());
+
+        // going idle is reported once
+        assert_eq!(network.round(), vec![]);
+    }
+
+    #[test]
+    fn nat_wakes_idle_network() {
+        let mut network = Network::new(&NAT, 1);
+
+        let packet = Packet { source: Some(0), destination: NAT_ADDRESS, x: 7, y: 42 };
+        assert_eq!(network.round(), vec![Traffic::SENT(packet)]);
+        assert_eq!(network.nat().last, Some(packet));
+
+        let woken = Packet { source: None, destination: 0, x: 7, y: 42 };
+        assert_eq!(network.round(), vec![Traffic::WAKE(woken)]);
+        assert_eq!(network.round(), vec![]);
+        assert_eq!(network.round(), vec![Traffic::WAKE(woken)]);
+        assert_eq!(network.nat().woken, vec![woken, woken]);
+    }
+
+    #[test]
+    fn injected_packets_reach_their_node() {
+        let mut network = Network::new(&NAT, 2);
+        network.round();
+
+        let packet = Packet { source: None, destination: 1, x: 5, y: 6 };
+        assert_eq!(network.inject(packet), Traffic::SENT(packet));
+
+        let stray = Packet { destination: 9, ..packet };
+        assert_eq!(network.inject(stray), Traffic::DROPPED(stray));
+
+        // only node 1 has anything to read, so the NAT stays quiet
+        assert_eq!(network.round(), vec![]);
+        assert!(network.nodes()[1].input_buffer.is_empty());
+    }
+
+    #[test]
+    fn unread_empty_input_is_not_repeated() {
+        let mut network = Network::new(&SPIN, 1);
+
+        for _ in 0..5 {
+            network.round();
+        }
+
+        assert_eq!(network.nodes()[0].input_buffer, [-1]);
+    }
+}
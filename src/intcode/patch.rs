@@ -0,0 +1,282 @@
+// a set of edits describing a variant of a program, e.g. the noun and verb
+// of day 2 or a game with its mode forced
+//
+// patch files have one edit per line, with '#' starting a comment:
+//
+//     0 = 2                  # write a single value
+//     10 = 1,2,3             # write consecutive values from address 10
+//     20..30 = 0             # fill addresses 20 to 29
+//     6: 1005 -> 1105        # write only if the old values match
+//     40: 1,2 -> 3,4
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Edit {
+    // writes consecutive values from the address, only if memory holds the
+    // expected values there when some are given
+    SET { address: usize, values: Vec<i32>, expected: Option<Vec<i32>> },
+    // writes the value to every address from start up to but not including
+    // end, kept as a range so that it is only expanded once it is in bounds
+    FILL { start: usize, end: usize, value: i32 },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Patch {
+    pub edits: Vec<Edit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatchError {
+    // the edit would touch memory past the end of the program
+    RANGE { address: usize, size: usize },
+    // the memory did not contain the values the edit expected to replace
+    MISMATCH { address: usize, expected: Vec<i32>, actual: Vec<i32> },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RANGE { address, size } => write!(
+                f,
+                "patch address {} is outside memory of size {}",
+                address, size,
+            ),
+            Self::MISMATCH { address, expected, actual } => write!(
+                f,
+                "patch at address {} expected {} but found {}",
+                address, join(expected), join(actual),
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+fn join(values: &[i32]) -> String {
+    values.iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+impl Patch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, address: usize, value: i32) -> &mut Self {
+        self.set_range(address, &[value])
+    }
+
+    pub fn set_range(&mut self, address: usize, values: &[i32]) -> &mut Self {
+        self.edits.push(Edit::SET {
+            address,
+            values: values.to_vec(),
+            expected: None,
+        });
+
+        self
+    }
+
+    pub fn fill(&mut self, addresses: Range<usize>, value: i32) -> &mut Self {
+        self.edits.push(Edit::FILL {
+            start: addresses.start,
+            end: addresses.end.max(addresses.start),
+            value,
+        });
+
+        self
+    }
+
+    // writes the values only if memory currently holds the expected ones
+    pub fn replace(
+        &mut self,
+        address: usize,
+        expected: &[i32],
+        values: &[i32]
+    ) -> &mut Self {
+        self.edits.push(Edit::SET {
+            address,
+            values: values.to_vec(),
+            expected: Some(expected.to_vec()),
+        });
+
+        self
+    }
+
+    // checks every edit against the original memory before writing any of
+    // them, so a failed patch leaves memory untouched
+    pub fn apply(&self, memory: &mut [i32]) -> Result<(), PatchError> {
+        for edit in self.edits.iter() {
+            let (address, end) = edit.span();
+
+            if end.is_none_or(|end| end > memory.len()) {
+                return Err(PatchError::RANGE { address, size: memory.len() });
+            }
+
+            if let Edit::SET { address, expected: Some(expected), .. } = edit {
+                let actual = &memory[*address..*address + expected.len()];
+
+                if actual != &expected[..] {
+                    return Err(PatchError::MISMATCH {
+                        address: *address,
+                        expected: expected.clone(),
+                        actual: actual.to_vec(),
+                    });
+                }
+            }
+        }
+
+        for edit in self.edits.iter() {
+            match edit {
+                Edit::SET { address, values, .. } => {
+                    memory[*address..*address + values.len()].copy_from_slice(values);
+                },
+                Edit::FILL { start, end, value } => memory[*start..*end].fill(*value),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn parse_values(text: &str) -> Result<Vec<i32>, String> {
+    let values = text.split(',')
+        .map(|item| {
+            let item = item.trim();
+            item.parse::<i32>()
+                .map_err(|_| format!("invalid value: {}", item))
+        })
+        .collect::<Result<Vec<i32>, String>>()?;
+
+    Ok(values)
+}
+
+fn parse_address(text: &str) -> Result<usize, String> {
+    let text = text.trim();
+    text.parse::<usize>()
+        .map_err(|_| format!("invalid address: {}", text))
+}
+
+impl Edit {
+    // the first address the edit touches and the address just past the last,
+    // which is None when it does not fit in an address at all
+    fn span(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::SET { address, values, expected } => {
+                let size = values.len().max(expected.as_ref().map_or(0, Vec::len));
+                (*address, address.checked_add(size))
+            },
+            Self::FILL { start, end, .. } => (*start, Some(*end)),
+        }
+    }
+}
+
+impl FromStr for Edit {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        if let Some((address, change)) = line.split_once(':') {
+            let (expected, values) = change.split_once("->")
+                .ok_or_else(|| format!("expected 'addr: old -> new': {}", line))?;
+
+            return Ok(Self::SET {
+                address: parse_address(address)?,
+                values: parse_values(values)?,
+                expected: Some(parse_values(expected)?),
+            });
+        }
+
+        let (target, values) = line.split_once('=')
+            .ok_or_else(|| format!("expected 'addr = values': {}", line))?;
+
+        let values = parse_values(values)?;
+
+        if let Some((start, end)) = target.split_once("..") {
+            let (start, end) = (parse_address(start)?, parse_address(end)?);
+
+            let [value] = values[..] else {
+                return Err(format!("a range takes a single fill value: {}", line));
+            };
+
+            if end < start {
+                return Err(format!("empty range: {}", line));
+            }
+
+            return Ok(Self::FILL { start, end, value });
+        }
+
+        Ok(Self::SET { address: parse_address(target)?, values, expected: None })
+    }
+}
+
+impl FromStr for Patch {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let edits = text.lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let line = line.split('#').next().unwrap().trim();
+                (index, line)
+            })
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(index, line)| {
+                line.parse::<Edit>()
+                    .map_err(|e| format!("line {}: {}", index + 1, e))
+            })
+            .collect::<Result<Vec<Edit>, String>>()?;
+
+        Ok(Self { edits })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(text: &str, memory: &mut [i32]) -> Result<(), PatchError> {
+        text.parse::<Patch>().unwrap().apply(memory)
+    }
+
+    #[test]
+    fn edits_are_applied_in_order() {
+        let mut memory = [0; 8];
+        apply("0 = 1,2\n4..7 = 9\n2: 0 -> 3", &mut memory).unwrap();
+        assert_eq!(memory, [1, 2, 3, 0, 9, 9, 9, 0]);
+    }
+
+    #[test]
+    fn address_past_the_end_of_memory() {
+        let error = PatchError::RANGE { address: usize::MAX, size: 8 };
+        let text = format!("{} = 1", usize::MAX);
+        assert_eq!(apply(&text, &mut [0; 8]), Err(error));
+    }
+
+    #[test]
+    fn huge_range_is_not_expanded() {
+        let error = PatchError::RANGE { address: 0, size: 8 };
+        let text = format!("0..{} = 0", usize::MAX);
+        assert_eq!(apply(&text, &mut [0; 8]), Err(error));
+    }
+
+    #[test]
+    fn failed_patch_leaves_memory_untouched() {
+        let mut memory = [0; 4];
+        let error = apply("0 = 5\n2: 1 -> 2", &mut memory).unwrap_err();
+        assert!(matches!(error, PatchError::MISMATCH { address: 2, .. }));
+        assert_eq!(memory, [0; 4]);
+    }
+}
@@ -0,0 +1,254 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// programs are stored either as the comma-separated text used by the puzzle
+// inputs, or in a binary container:
+//
+//     magic       4 bytes   "ICB\0"
+//     version     1 byte    currently 1
+//     word size   1 byte    bits per word, 32 or 64
+//     length      varint    number of words
+//     words       varints   zigzag encoded so small negatives stay short
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    TEXT,
+    BINARY,
+}
+
+pub const MAGIC: [u8; 4] = *b"ICB\0";
+pub const VERSION: u8 = 1;
+pub const WORD_SIZE: u8 = 32;
+
+// parses the comma-separated text format used by the puzzle inputs; empty
+// text is an empty program, just as to_text writes one
+pub fn parse(text: &str) -> Result<Vec<i32>, String> {
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    text.split(',')
+        .map(|item| {
+            let item = item.trim();
+            item.parse::<i32>()
+                .map_err(|_| format!("invalid intcode word: {:?}", item))
+        })
+        .collect()
+}
+
+pub fn to_text(words: &[i32]) -> String {
+    words.iter()
+        .map(|word| word.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], position: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*position)
+            .ok_or("unexpected end of binary program")?;
+
+        *position += 1;
+
+        // the tenth byte only has room for the top bit
+        if shift == 63 && byte > 1 {
+            break;
+        }
+
+        value |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(format!("varint too long at byte {}", *position))
+}
+
+pub fn encode(words: &[i32]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    bytes.push(WORD_SIZE);
+
+    write_varint(&mut bytes, words.len() as u64);
+
+    for &word in words {
+        let word = word as i64;
+        write_varint(&mut bytes, ((word << 1) ^ (word >> 63)) as u64);
+    }
+
+    bytes
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Vec<i32>, String> {
+    if !bytes.starts_with(&MAGIC) {
+        return Err("not a binary intcode program".to_string());
+    }
+
+    let (version, word_size) = match bytes.get(4..6) {
+        Some(&[version, word_size]) => (version, word_size),
+        _ => return Err("truncated binary program header".to_string()),
+    };
+
+    if version != VERSION {
+        return Err(format!("unsupported binary program version {}", version));
+    }
+
+    if word_size != 32 && word_size != 64 {
+        return Err(format!("unsupported word size {}", word_size));
+    }
+
+    let mut position = 6;
+    let length = read_varint(bytes, &mut position)?;
+
+    // every word takes at least one byte, which bounds the allocation
+    if length > (bytes.len() - position) as u64 {
+        return Err(format!("binary program claims {} words", length));
+    }
+
+    let words = (0..length)
+        .map(|index| {
+            let value = read_varint(bytes, &mut position)?;
+            let word = (value >> 1) as i64 ^ -((value & 1) as i64);
+
+            i32::try_from(word)
+                .map_err(|_| format!("word {} does not fit in 32 bits: {}", index, word))
+        })
+        .collect::<Result<Vec<i32>, String>>()?;
+
+    if position != bytes.len() {
+        return Err(format!("{} trailing bytes after program", bytes.len() - position));
+    }
+
+    Ok(words)
+}
+
+pub fn detect(bytes: &[u8]) -> Format {
+    match bytes.starts_with(&MAGIC) {
+        true => Format::BINARY,
+        false => Format::TEXT,
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// loads a program in either format
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Vec<i32>> {
+    let bytes = fs::read(path)?;
+
+    match detect(&bytes) {
+        Format::BINARY => decode(&bytes).map_err(invalid),
+        Format::TEXT => {
+            let text = String::from_utf8(bytes)
+                .map_err(|_| invalid("program is not valid UTF-8 text".to_string()))?;
+            parse(&text).map_err(invalid)
+        },
+    }
+}
+
+pub fn save<P: AsRef<Path>>(path: P, words: &[i32], format: Format) -> io::Result<()> {
+    match format {
+        Format::BINARY => fs::write(path, encode(words)),
+        Format::TEXT => fs::write(path, to_text(words) + "\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(word_size: u8) -> Vec<u8> {
+        [&MAGIC[..], &[VERSION, word_size]].concat()
+    }
+
+    #[test]
+    fn round_trip() {
+        let programs: [&[i32]; 4] = [
+            &[],
+            &[1, 0, 0, 3, 99],
+            &[-1, -64, 64, -65, 300, -300],
+            &[i32::MIN, i32::MAX, 0, i32::MIN + 1, i32::MAX - 1],
+        ];
+
+        for words in programs {
+            assert_eq!(decode(&encode(words)).as_deref(), Ok(words));
+            assert_eq!(parse(&to_text(words)).as_deref(), Ok(words));
+        }
+    }
+
+    #[test]
+    fn small_negatives_stay_short() {
+        assert_eq!(encode(&[-1, 1, -64]).len(), header(32).len() + 4);
+    }
+
+    #[test]
+    fn empty_text_is_empty_program() {
+        assert_eq!(parse(""), Ok(Vec::new()));
+        assert_eq!(parse(" \n"), Ok(Vec::new()));
+        assert!(parse("1,,2").is_err());
+    }
+
+    #[test]
+    fn truncated_header() {
+        let error = Err("truncated binary program header".to_string());
+        assert_eq!(decode(&MAGIC), error);
+        assert_eq!(decode(&header(32)[..5]), error);
+    }
+
+    #[test]
+    fn truncated_varint() {
+        let error = Err("unexpected end of binary program".to_string());
+
+        // the length, then a word, end part way through
+        assert_eq!(decode(&[header(32), vec![0x80]].concat()), error);
+        assert_eq!(decode(&[header(32), vec![1, 0x81]].concat()), error);
+    }
+
+    #[test]
+    fn varint_too_long() {
+        let bytes = [header(32), vec![1], vec![0x80; 10], vec![0]].concat();
+        assert_eq!(decode(&bytes), Err("varint too long at byte 17".to_string()));
+
+        // ten bytes which would need more than 64 bits
+        let bytes = [header(32), vec![1], vec![0x80; 9], vec![2]].concat();
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let bytes = [encode(&[1, 2]), vec![0, 0]].concat();
+        assert_eq!(decode(&bytes), Err("2 trailing bytes after program".to_string()));
+    }
+
+    #[test]
+    fn word_size_64() {
+        let mut bytes = header(64);
+        write_varint(&mut bytes, 2);
+        write_varint(&mut bytes, 6);
+        write_varint(&mut bytes, 1 << 32);
+        assert_eq!(
+            decode(&bytes),
+            Err("word 1 does not fit in 32 bits: 2147483648".to_string()),
+        );
+
+        let mut bytes = header(64);
+        write_varint(&mut bytes, 1);
+        write_varint(&mut bytes, 5);
+        assert_eq!(decode(&bytes), Ok(vec![-3]));
+    }
+}
@@ -0,0 +1,286 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{Computer, Fault, Signal};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    INPUT,
+    OUTPUT,
+}
+
+// a value consumed or produced by the program, tagged with the number of
+// instructions that had completed when the I/O instruction executed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub step: u64,
+    pub direction: Direction,
+    pub value: i32,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::INPUT => "input",
+            Direction::OUTPUT => "output",
+        };
+
+        write!(f, "{} {} {}", self.step, direction, self.value)
+    }
+}
+
+impl std::str::FromStr for Event {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let parts = line.split_whitespace().collect::<Vec<&str>>();
+
+        let [step, direction, value] = parts[..] else {
+            return Err(format!("expected 'step direction value': {}", line));
+        };
+
+        let step = step.parse::<u64>()
+            .map_err(|_| format!("invalid step: {}", step))?;
+
+        let direction = match direction {
+            "input" => Direction::INPUT,
+            "output" => Direction::OUTPUT,
+            _ => return Err(format!("invalid direction: {}", direction)),
+        };
+
+        let value = value.parse::<i32>()
+            .map_err(|_| format!("invalid value: {}", value))?;
+
+        Ok(Self { step, direction, value })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recording {
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    // the program produced a different event than the recording, or ran out
+    // of events on one side before the other
+    DIVERGED {
+        index: usize,
+        expected: Option<Event>,
+        actual: Option<Event>,
+    },
+    // the program asked for input where the recording has none
+    STARVED {
+        index: usize,
+        step: u64,
+        expected: Option<Event>,
+    },
+    // the program stopped for a reason other than halting or asking for
+    // input, such as running out of gas, and would make no further progress
+    STOPPED {
+        index: usize,
+        step: u64,
+        signal: Signal,
+    },
+    FAULT(Fault),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn describe(event: &Option<Event>) -> String {
+            match event {
+                Some(event) => format!("'{}'", event),
+                None => "nothing".to_string(),
+            }
+        }
+
+        match self {
+            Self::DIVERGED { index, expected, actual } => write!(
+                f,
+                "replay diverged at event #{}: expected {}, got {}",
+                index, describe(expected), describe(actual),
+            ),
+            Self::STARVED { index, step, expected } => write!(
+                f,
+                "replay diverged at event #{}: program requested input at \
+                step {}, expected {}",
+                index, step, describe(expected),
+            ),
+            Self::STOPPED { index, step, signal } => write!(
+                f,
+                "replay stopped at event #{}: program stopped with {:?} at step {}",
+                index, signal, step,
+            ),
+            Self::FAULT(fault) => write!(f, "replay faulted: {}", fault),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<Fault> for ReplayError {
+    fn from(fault: Fault) -> Self {
+        Self::FAULT(fault)
+    }
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, step: u64, direction: Direction, value: i32) {
+        self.events.push(Event { step, direction, value });
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut text = String::from("# step direction value\n");
+
+        for event in self.events.iter() {
+            text.push_str(&format!("{}\n", event));
+        }
+
+        fs::write(path, text)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+
+        let events = text.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.parse::<Event>()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .collect::<io::Result<Vec<Event>>>()?;
+
+        Ok(Self { events })
+    }
+
+    // runs the program on the given computer, supplying the recorded inputs
+    // whenever it asks for one and checking that every event, including the
+    // step at which it happened, matches the recording
+    //
+    // the replay is checked against a recording of its own; a recording the
+    // caller already had running is put back afterwards, with the replayed
+    // events added to it
+    pub fn replay(&self, computer: &mut Computer) -> Result<(), ReplayError> {
+        let previous = computer.take_recording();
+        computer.record();
+
+        let result = self.check(computer);
+
+        let replayed = computer.take_recording().unwrap_or_default();
+
+        if let Some(mut recording) = previous {
+            recording.events.extend(replayed.events);
+            computer.recording = Some(recording);
+        }
+
+        result
+    }
+
+    fn check(&self, computer: &mut Computer) -> Result<(), ReplayError> {
+        let mut index = 0;
+
+        loop {
+            let signal = computer.step()?;
+
+            let actual = &computer.recording().unwrap().events;
+
+            while index < actual.len() {
+                let expected = self.events.get(index).copied();
+
+                if expected != Some(actual[index]) {
+                    return Err(ReplayError::DIVERGED {
+                        index,
+                        expected,
+                        actual: Some(actual[index]),
+                    });
+                }
+
+                index += 1;
+            }
+
+            match signal {
+                Some(Signal::HALT) => break,
+                Some(Signal::INPUT) => {
+                    let expected = self.events.get(index).copied();
+
+                    match expected {
+                        Some(Event { direction: Direction::INPUT, value, .. }) => {
+                            computer.input_buffer.push_back(value);
+                        },
+                        _ => return Err(ReplayError::STARVED {
+                            index,
+                            step: computer.steps(),
+                            expected,
+                        }),
+                    }
+                },
+                Some(signal) => return Err(ReplayError::STOPPED {
+                    index,
+                    step: computer.steps(),
+                    signal,
+                }),
+                None => (),
+            }
+        }
+
+        if index < self.events.len() {
+            return Err(ReplayError::DIVERGED {
+                index,
+                expected: Some(self.events[index]),
+                actual: None,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // echoes three inputs back as outputs
+    const ECHO: [i32; 14] = [3, 13, 4, 13, 3, 13, 4, 13, 3, 13, 4, 13, 99, 0];
+
+    fn recorded() -> Recording {
+        let mut computer = Computer::new(&ECHO);
+        computer.record().input_buffer.extend([7, 8, 9]);
+        computer.execute_program();
+        computer.take_recording().unwrap()
+    }
+
+    #[test]
+    fn replay_matches_recording() {
+        let mut computer = Computer::new(&ECHO);
+        recorded().replay(&mut computer).unwrap();
+        assert_eq!(computer.output_buffer, [7, 8, 9]);
+    }
+
+    #[test]
+    fn replay_keeps_callers_recording() {
+        let mut computer = Computer::new(&ECHO);
+        computer.record().send(1);
+
+        recorded().replay(&mut computer).unwrap();
+
+        let events = &computer.recording().unwrap().events;
+        assert_eq!(events.len(), 7);
+        assert_eq!(events[0], Event { step: 0, direction: Direction::OUTPUT, value: 1 });
+    }
+
+    #[test]
+    fn replay_stops_when_out_of_gas() {
+        let mut computer = Computer::new(&ECHO);
+        computer.with_gas_limit(Some(3));
+
+        let error = recorded().replay(&mut computer).unwrap_err();
+        assert!(matches!(error, ReplayError::STOPPED { signal: Signal::GAS, .. }));
+    }
+}
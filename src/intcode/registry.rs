@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{Computer, Fault, OpCode};
+
+// how an instruction uses each of its parameters; read parameters may be
+// given in any parameter mode, write parameters name the destination address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    READ,
+    WRITE,
+}
+
+// what the interpreter should do once an operation has executed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    NEXT,
+    JUMP(usize),
+    HALT,
+    // the instruction cannot complete yet and will be retried on resume
+    BLOCK,
+}
+
+pub trait Operation: Send + Sync {
+    fn mnemonic(&self) -> &str;
+
+    fn roles(&self) -> &[Role];
+
+    // params holds the resolved address of each parameter, in order
+    fn execute(
+        &self,
+        computer: &mut Computer,
+        params: &[usize]
+    ) -> Result<Effect, Fault>;
+
+    fn arity(&self) -> usize {
+        self.roles().len()
+    }
+
+    fn size(&self) -> usize {
+        self.arity() + 1
+    }
+}
+
+#[derive(Clone)]
+pub struct InstructionSet {
+    operations: HashMap<i32, Arc<dyn Operation>>,
+}
+
+impl InstructionSet {
+    pub fn empty() -> Self {
+        Self { operations: HashMap::new() }
+    }
+
+    // registers an operation under an opcode id, replacing any operation
+    // previously registered under the same id
+    pub fn register<T>(&mut self, id: i32, operation: T) -> &mut Self
+    where
+        T: Operation + 'static,
+    {
+        assert!(
+            (1..100).contains(&id),
+            "opcode id must fit in two digits: {}", id
+        );
+
+        self.operations.insert(id, Arc::new(operation));
+        self
+    }
+
+    pub fn unregister(&mut self, id: i32) -> &mut Self {
+        self.operations.remove(&id);
+        self
+    }
+
+    pub fn get(&self, id: i32) -> Option<&Arc<dyn Operation>> {
+        self.operations.get(&id)
+    }
+
+    pub fn ids(&self) -> Vec<i32> {
+        let mut ids = self.operations.keys().copied().collect::<Vec<i32>>();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        let mut instruction_set = Self::empty();
+
+        for opcode in OpCode::ALL {
+            instruction_set.register(opcode.id(), opcode);
+        }
+
+        instruction_set
+    }
+}
@@ -0,0 +1,66 @@
+// equality and hashing of the resumable state of a computer: its memory,
+// instruction pointer and I/O buffers
+//
+// configuration such as the instruction set or step limit, counters and
+// instrumentation are not part of the state, so two computers which reached
+// the same state by different routes compare equal
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::Computer;
+
+// a well distributed hash of a single memory cell; the memory hash is the
+// xor of this over every cell, so a write can update it in constant time
+pub(super) fn mix(address: usize, value: i32) -> u64 {
+    let mut x = (address as u64) << 32 | (value as u32 as u64);
+
+    // splitmix64 finaliser
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+pub(super) fn memory_hash(memory: &[i32]) -> u64 {
+    memory.iter()
+        .enumerate()
+        .fold(0, |hash, (address, &value)| hash ^ mix(address, value))
+}
+
+impl Computer {
+    // a cheap fingerprint of the state, stable across runs of the process
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // recomputes the memory hash after memory was modified in bulk rather
+    // than through write()
+    pub(super) fn rehash(&mut self) -> &mut Self {
+        self.memory_hash = memory_hash(&self.memory);
+        self
+    }
+}
+
+impl PartialEq for Computer {
+    fn eq(&self, other: &Self) -> bool {
+        self.memory_hash == other.memory_hash
+            && self.instruction_pointer == other.instruction_pointer
+            && self.input_buffer == other.input_buffer
+            && self.output_buffer == other.output_buffer
+            && self.memory == other.memory
+    }
+}
+
+impl Eq for Computer {}
+
+impl Hash for Computer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.memory_hash.hash(state);
+        self.instruction_pointer.hash(state);
+        self.input_buffer.hash(state);
+        self.output_buffer.hash(state);
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+// just enough JSON for the tooling protocols spoken by this crate
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    NULL,
+    BOOL(bool),
+    NUMBER(f64),
+    STRING(String),
+    ARRAY(Vec<Value>),
+    OBJECT(BTreeMap<String, Value>),
+}
+
+impl Value {
+    pub fn object<K: Into<String>>(entries: Vec<(K, Value)>) -> Self {
+        Self::OBJECT(
+            entries.into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect()
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::OBJECT(entries) => entries.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::BOOL(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::NUMBER(value) if value.fract() == 0.0 => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::STRING(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Self::ARRAY(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::BOOL(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Self::NUMBER(value as f64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::NUMBER(value as f64)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Self::NUMBER(value as f64)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Self::NUMBER(value as f64)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::STRING(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::STRING(value)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(values: Vec<Value>) -> Self {
+        Self::ARRAY(values)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NULL => write!(f, "null"),
+            Self::BOOL(value) => write!(f, "{}", value),
+            Self::NUMBER(value) if value.is_finite() => write!(f, "{}", value),
+            Self::NUMBER(_) => write!(f, "null"),
+            Self::STRING(value) => write_string(f, value),
+            Self::ARRAY(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            Self::OBJECT(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        Err(format!("{} at byte {}", message, self.position))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), String> {
+        let end = self.position + literal.len();
+
+        if self.text.get(self.position..end) != Some(literal.as_bytes()) {
+            return self.error(&format!("expected {:?}", literal));
+        }
+
+        self.position = end;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'n') => self.expect("null").map(|_| Value::NULL),
+            Some(b't') => self.expect("true").map(|_| Value::BOOL(true)),
+            Some(b'f') => self.expect("false").map(|_| Value::BOOL(false)),
+            Some(b'"') => self.parse_string().map(Value::STRING),
+            Some(b'[') => self.parse_array(),
+            Some(b'{') => self.parse_object(),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => self.error("unexpected character"),
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, String> {
+        let start = self.position;
+
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+
+        let text = std::str::from_utf8(&self.text[start..self.position])
+            .unwrap();
+
+        match text.parse::<f64>() {
+            Ok(value) => Ok(Value::NUMBER(value)),
+            Err(_) => self.error("invalid number"),
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, String> {
+        let end = self.position + 4;
+
+        let digits = self.text.get(self.position..end)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok());
+
+        match digits {
+            Some(value) => {
+                self.position = end;
+                Ok(value)
+            },
+            None => self.error("invalid unicode escape"),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+
+        let mut bytes = Vec::new();
+
+        loop {
+            let Some(byte) = self.peek() else {
+                return self.error("unterminated string");
+            };
+
+            self.position += 1;
+
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let Some(escape) = self.peek() else {
+                        return self.error("unterminated escape");
+                    };
+
+                    self.position += 1;
+
+                    let c = match escape {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut code = self.parse_hex4()?;
+
+                            // surrogate pairs encode characters outside the
+                            // basic multilingual plane
+                            if (0xd800..0xdc00).contains(&code) {
+                                self.expect("\\u")?;
+                                let low = self.parse_hex4()?;
+                                code = 0x10000
+                                    + ((code - 0xd800) << 10)
+                                    + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+
+                            char::from_u32(code).unwrap_or('\u{fffd}')
+                        },
+                        _ => return self.error("invalid escape"),
+                    };
+
+                    let mut buffer = [0; 4];
+                    bytes.extend(c.encode_utf8(&mut buffer).as_bytes());
+                },
+                byte => bytes.push(byte),
+            }
+        }
+
+        String::from_utf8(bytes).or_else(|_| self.error("invalid utf-8"))
+    }
+
+    fn parse_array(&mut self) -> Result<Value, String> {
+        self.expect("[")?;
+
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+
+        if let Some(b']') = self.peek() {
+            self.position += 1;
+            return Ok(Value::ARRAY(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Value::ARRAY(values));
+                },
+                _ => return self.error("expected ',' or ']'"),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, String> {
+        self.expect("{")?;
+
+        let mut entries = BTreeMap::new();
+
+        self.skip_whitespace();
+
+        if let Some(b'}') = self.peek() {
+            self.position += 1;
+            return Ok(Value::OBJECT(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(":")?;
+            let value = self.parse_value()?;
+            entries.insert(key, value);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Value::OBJECT(entries));
+                },
+                _ => return self.error("expected ',' or '}'"),
+            }
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Value, String> {
+    let mut parser = Parser { text: text.as_bytes(), position: 0 };
+
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+
+    if parser.position != text.len() {
+        return parser.error("trailing characters");
+    }
+
+    Ok(value)
+}
@@ -0,0 +1,5 @@
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod ffi;
+pub mod intcode;
+pub mod json;
@@ -1,67 +1,31 @@
-mod intcode;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
+#![allow(clippy::upper_case_acronyms)]
+#![allow(clippy::let_and_return)]
+#![allow(clippy::bool_assert_comparison)]
+
+use advent_of_code_2019::intcode;
+
+mod answer;
+mod cli;
+mod expected;
+mod runner;
+mod solution;
+
+solution::solutions! {
+    1 => day01::Day01,
+    2 => day02::Day02,
+    3 => day03::Day03,
+    4 => day04::Day04,
+    5 => day05::Day05,
+    6 => day06::Day06,
+    7 => day07::Day07,
+}
 
 fn main() {
-    let result = day01::part1();
-    println!("Day 1 Part 1: {:?}", result);
-    assert_eq!(3427972, result);
-
-    let result = day01::part2();
-    println!("      Part 2: {:?}", result);
-    assert_eq!(5139078, result);
-
-    let result = day02::part1();
-    println!("Day 2 Part 1: {:?}", result);
-    assert_eq!(7594646, result);
-
-    let result = day02::part2();
-    println!("      Part 2: {:?}", result);
-    assert_eq!(3376, result);
-
-    let result = day03::part1();
-    println!("Day 3 Part 1: {:?}", result);
-    assert_eq!(529, result);
-
-    let result = day03::part2();
-    println!("      Part 2: {:?}", result);
-    assert_eq!(20386, result);
-
-    let result = day04::part1();
-    println!("Day 4 Part 1: {:?}", result);
-    assert_eq!(945, result);
-
-    let result = day04::part2();
-    println!("      Part 2: {:?}", result);
-    assert_eq!(617, result);
-
-    let result = day05::part1();
-    println!("Day 5 Part 1: {:?}", result);
-    assert_eq!(15314507, result);
-
-    let result = day05::part2();
-    println!("      Part 2: {:?}", result);
-    assert_eq!(652726, result);
-
-    let result = day06::part1();
-    println!("Day 6 Part 1: {:?}", result);
-    assert_eq!(122782, result);
-
-    let result = day06::part2();
-    println!("      Part 2: {:?}", result);
-    assert_eq!(271, result);
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
 
-    let result = day07::part1();
-    println!("Day 7 Part 1: {:?}", result);
-    assert_eq!(18812, result);
+    if let Some("intcode") = args.first().map(String::as_str) {
+        std::process::exit(cli::intcode(&args[1..]));
+    }
 
-    let result = day07::part2();
-    println!("      Part 2: {:?}", result);
-    // assert_eq!(271, result);
+    std::process::exit(runner::main(&args, &registry()));
 }
@@ -0,0 +1,306 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use crate::answer::Answer;
+use crate::expected::{self, Check, Expected};
+use crate::solution::Entry;
+
+const USAGE: &str = "\
+usage: run [<days> [<part>]] [options]
+       verify [<days> [<part>]] [options]
+       intcode <command> [<args>...]
+
+verify prints a table of every answer checked against the expected answers,
+and fails if any did not match
+
+    <days>              a day such as 3, a range such as 1-5, or all (the
+                        default)
+    <part>              1 or 2; both parts are run by default
+    --input <file>      solve a single day with a different puzzle input,
+                        or with stdin when the file is -
+    --answers <file>    read the expected answers from a different file
+                        (default answers.txt)";
+
+struct Selection {
+    days: Vec<u32>,
+    parts: Vec<u32>,
+    input: Option<String>,
+    answers: Option<String>,
+}
+
+fn parse_day(text: &str) -> Result<u32, String> {
+    text.parse::<u32>().map_err(|_| format!("invalid day: {}", text))
+}
+
+fn parse_days(text: &str, registry: &[Entry]) -> Result<Vec<u32>, String> {
+    let registered = |day: &u32| registry.iter().any(|entry| entry.day == *day);
+
+    if text == "all" {
+        return Ok(registry.iter().map(|entry| entry.day).collect());
+    }
+
+    if let Some((first, last)) = text.split_once('-') {
+        let days = (parse_day(first)?..=parse_day(last)?)
+            .filter(registered)
+            .collect::<Vec<u32>>();
+
+        return match days.is_empty() {
+            true => Err(format!("no days in {} have been solved", text)),
+            false => Ok(days),
+        };
+    }
+
+    match parse_day(text)? {
+        day if registered(&day) => Ok(vec![day]),
+        day => Err(format!("day {} has not been solved", day)),
+    }
+}
+
+fn parse_selection(args: &[String], registry: &[Entry]) -> Result<Selection, String> {
+    let mut positional = Vec::new();
+    let mut input = None;
+    let mut answers = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                let path = args.next().ok_or("--input needs a file")?;
+                input = Some(path.clone());
+            },
+            "--answers" => {
+                let path = args.next().ok_or("--answers needs a file")?;
+                answers = Some(path.clone());
+            },
+            flag if flag.starts_with("--") => {
+                return Err(format!("unknown option: {}", flag));
+            },
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let days = match positional.first() {
+        Some(days) => parse_days(days, registry)?,
+        None => parse_days("all", registry)?,
+    };
+
+    let parts = match positional.get(1) {
+        Some(&"1") => vec![1],
+        Some(&"2") => vec![2],
+        Some(part) => return Err(format!("invalid part: {}", part)),
+        None => vec![1, 2],
+    };
+
+    if positional.len() > 2 {
+        return Err(format!("unexpected argument: {}", positional[2]));
+    }
+
+    if input.is_some() && days.len() != 1 {
+        return Err("--input needs a single day".to_string());
+    }
+
+    Ok(Selection { days, parts, input, answers })
+}
+
+fn read_input(entry: &Entry, path: Option<&str>) -> Result<String, String> {
+    match path {
+        Some("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)
+                .map_err(|e| format!("stdin: {}", e))?;
+            Ok(text)
+        },
+        Some(path) => {
+            fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))
+        },
+        None => {
+            let path = entry.input_path();
+            fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))
+        },
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    RUN,
+    VERIFY,
+}
+
+thread_local! {
+    // the message and location of the last panic in a solution
+    static PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// solves a part, turning a panic in the solution into an error so that one
+// broken day does not stop the others from being checked
+fn solve_part(entry: &Entry, part: u32, input: &str) -> Result<Answer, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| entry.solve(part, input)))
+        .map_err(|_| {
+            PANIC.with(|message| message.borrow_mut().take())
+                .unwrap_or_else(|| "panicked".to_string())
+        })
+}
+
+fn load_expected(selection: &Selection, mode: Mode) -> Result<Expected, String> {
+    let path = selection.answers.as_deref().unwrap_or(expected::DEFAULT_PATH);
+
+    // answers are only needed to verify, so run works from any directory
+    if mode == Mode::RUN && selection.answers.is_none() && !Path::new(path).exists() {
+        return Ok(Expected::default());
+    }
+
+    Expected::load(path)
+}
+
+// prints one part's result as a table row when verifying, or as the familiar
+// 'Day N Part M' lines otherwise; part is None for errors affecting a whole
+// day, and multi-line answers follow their row, indented
+fn print_row(
+    mode: Mode,
+    first: bool,
+    day: u32,
+    part: Option<u32>,
+    status: &dyn fmt::Display,
+    answer: Option<&Answer>,
+    note: &str,
+) {
+    let (inline, block) = match answer {
+        Some(answer) if answer.is_multiline() => (String::new(), answer.to_string()),
+        Some(answer) => (answer.to_string(), String::new()),
+        None => (String::new(), String::new()),
+    };
+
+    let row = match (mode, part) {
+        (Mode::VERIFY, Some(part)) => {
+            format!("{:>3}  {:>4}  {:<7}  {}{}", day, part, status, inline, note)
+        },
+        (Mode::VERIFY, None) => {
+            format!("{:>3}  {:>4}  {:<7}  {}{}", day, "-", status, inline, note)
+        },
+        (Mode::RUN, Some(part)) if first => {
+            format!("Day {} Part {}: {}{}", day, part, inline, note)
+        },
+        (Mode::RUN, Some(part)) => format!("      Part {}: {}{}", part, inline, note),
+        (Mode::RUN, None) => format!("Day {}: {}{}", day, inline, note),
+    };
+
+    println!("{}", row.trim_end());
+
+    for line in block.lines() {
+        println!("    {}", line);
+    }
+}
+
+fn solve(selection: &Selection, registry: &[Entry], mode: Mode) -> Result<i32, String> {
+    let expected = load_expected(selection, mode)?;
+
+    let entries = registry.iter()
+        .filter(|entry| selection.days.contains(&entry.day));
+
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+
+    if mode == Mode::VERIFY {
+        println!("day  part  status   answer");
+    }
+
+    let hook = panic::take_hook();
+
+    panic::set_hook(Box::new(|info| {
+        let message = info.to_string().replace('\n', " ");
+        PANIC.with(|panic| *panic.borrow_mut() = Some(message));
+    }));
+
+    for entry in entries {
+        let input = match read_input(entry, selection.input.as_deref()) {
+            Ok(input) => input,
+            Err(message) => {
+                failed += 1;
+                print_row(mode, true, entry.day, None, &"FAIL", None, &format!("error: {}", message));
+                continue;
+            },
+        };
+
+        for (index, &part) in selection.parts.iter().enumerate() {
+            let result = match solve_part(entry, part, &input) {
+                Ok(result) => result,
+                Err(message) => {
+                    failed += 1;
+                    let note = format!("error: {}", message);
+                    print_row(mode, index == 0, entry.day, Some(part), &"FAIL", None, &note);
+                    continue;
+                },
+            };
+
+            let check = expected.check(entry.day, part, &input, &result);
+
+            let note = match &check {
+                Check::PASS => {
+                    passed += 1;
+                    String::new()
+                },
+                Check::FAIL { expected } => {
+                    failed += 1;
+                    format!("  (expected {})", expected.serialize())
+                },
+                // unknown answers are only worth pointing out when verifying
+                Check::UNKNOWN => {
+                    unknown += 1;
+                    match mode {
+                        Mode::VERIFY => format!("  (input {})", expected::input_key(&input)),
+                        Mode::RUN => String::new(),
+                    }
+                },
+            };
+
+            print_row(mode, index == 0, entry.day, Some(part), &check, Some(&result), &note);
+        }
+    }
+
+    panic::set_hook(hook);
+
+    if mode == Mode::VERIFY {
+        println!("{} passed, {} failed, {} unknown", passed, failed, unknown);
+    }
+
+    match failed {
+        0 => Ok(0),
+        _ => Ok(1),
+    }
+}
+
+fn report(result: Result<i32, String>) -> i32 {
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            1
+        },
+    }
+}
+
+// with no arguments every day is run, as though 'run all' had been given
+pub fn main(args: &[String], registry: &[Entry]) -> i32 {
+    let (mode, args) = match args.first().map(String::as_str) {
+        Some("run") => (Mode::RUN, &args[1..]),
+        Some("verify") => (Mode::VERIFY, &args[1..]),
+        None => (Mode::RUN, args),
+        _ => {
+            eprintln!("{}", USAGE);
+            return 2;
+        },
+    };
+
+    let selection = match parse_selection(args, registry) {
+        Ok(selection) => selection,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return 2;
+        },
+    };
+
+    report(solve(&selection, registry, mode))
+}
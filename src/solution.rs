@@ -0,0 +1,54 @@
+// a day's puzzle solution, taking the full text of the puzzle input and
+// returning the answer to each part
+use std::path::PathBuf;
+
+use crate::answer::Answer;
+
+pub trait Solution {
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    fn part1(input: &str) -> Self::Part1;
+    fn part2(input: &str) -> Self::Part2;
+}
+
+// a registered solution, with the types of its answers erased
+pub struct Entry {
+    pub day: u32,
+    parts: [fn(&str) -> Answer; 2],
+}
+
+impl Entry {
+    pub fn new<S: Solution>(day: u32) -> Self {
+        Self {
+            day,
+            parts: [
+                |input| S::part1(input).into(),
+                |input| S::part2(input).into(),
+            ],
+        }
+    }
+
+    pub fn input_path(&self) -> PathBuf {
+        PathBuf::from(format!("input/day{:02}/input.txt", self.day))
+    }
+
+    // parts are numbered from 1
+    pub fn solve(&self, part: u32, input: &str) -> Answer {
+        self.parts[part as usize - 1](input)
+    }
+}
+
+// declares each day's module and lists its solution in the registry, so that
+// adding a day takes a single line
+macro_rules! solutions {
+    ($($day:literal => $module:ident::$solution:ident,)*) => {
+        $(mod $module;)*
+
+        fn registry() -> Vec<solution::Entry> {
+            vec![$(solution::Entry::new::<$module::$solution>($day),)*]
+        }
+    };
+}
+
+pub(crate) use solutions;